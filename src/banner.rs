@@ -1,45 +1,140 @@
+use std::collections::VecDeque;
+
 use foxglove::LazyChannel;
 use foxglove::schemas::{
     Color, FrameTransform, SceneEntity, SceneEntityDeletion, SceneUpdate, TextPrimitive, Vector3,
 };
 
-use crate::landing::LandingStatus;
+use crate::events::Event;
+use crate::landing::{LandingReport, LandingStatus};
+use crate::leaderboard::Standing;
 
 static BANNER: LazyChannel<SceneUpdate> = LazyChannel::new("/banner");
 
-#[derive(Default)]
-pub struct Banner(SceneUpdate);
+/// Duration of the fade in and out of a banner, in seconds.
+const FADE_DURATION: f32 = 0.3;
+
+/// Maximum opacity of a banner.
+const MAX_ALPHA: f64 = 0.75;
+
+/// Font size for headline banners.
+const HEADLINE_FONT_SIZE: f64 = 48.0;
+
+/// Font size for multi-line banners.
+const SUMMARY_FONT_SIZE: f64 = 24.0;
+
+/// A banner message, displayed above the lander.
+#[derive(Debug, Clone)]
+pub struct Banner {
+    text: String,
+    color: (f64, f64, f64),
+    font_size: f64,
+    /// How long the banner is displayed, in seconds, or `None` to display it until another banner
+    /// is queued behind it.
+    duration: Option<f32>,
+}
 
 impl Banner {
-    fn new(text: &str, r: f64, g: f64, b: f64) -> Self {
-        Self(SceneUpdate {
-            entities: vec![SceneEntity {
-                id: "banner".into(),
-                frame_id: "banner".into(),
-                texts: vec![TextPrimitive {
-                    pose: None,
-                    billboard: true,
-                    font_size: 48.0,
-                    scale_invariant: true,
-                    color: Some(Color { r, g, b, a: 0.75 }),
-                    text: text.to_string(),
-                }],
-                ..Default::default()
-            }],
-            ..Default::default()
-        })
+    fn new(text: impl Into<String>, r: f64, g: f64, b: f64) -> Self {
+        Self {
+            text: text.into(),
+            color: (r, g, b),
+            font_size: HEADLINE_FONT_SIZE,
+            duration: None,
+        }
+    }
+
+    fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = Some(duration);
+        self
     }
+
+    fn with_font_size(mut self, font_size: f64) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
     pub fn press_start() -> Self {
         Self::new("PRESS START", 1.0, 0.0, 1.0)
     }
 
+    /// Returns a 3-2-1 countdown, to be displayed before the game starts.
+    pub fn countdown() -> Vec<Self> {
+        ["3", "2", "1"]
+            .into_iter()
+            .map(|text| Self::new(text, 1.0, 1.0, 1.0).with_duration(1.0))
+            .collect()
+    }
+
     pub fn landing_status(status: LandingStatus) -> Self {
         let ((r, g, b), text) = match status {
             LandingStatus::Landed => ((0.0, 1.0, 0.0), "LANDED"),
             LandingStatus::Missed => ((1.0, 1.0, 0.0), "MISSED"),
             LandingStatus::Crashed => ((1.0, 0.0, 0.0), "YOU DIED"),
+            LandingStatus::Aborted => ((0.0, 0.6, 1.0), "ABORTED"),
+        };
+        Self::new(text, r, g, b).with_duration(2.0)
+    }
+
+    pub fn time_expired() -> Self {
+        Self::new("TIME EXPIRED", 1.0, 0.0, 0.0)
+    }
+
+    /// Returns a multi-line summary of the landing report, and where it placed on the leaderboard.
+    pub fn landing_summary(report: &LandingReport, standing: Standing) -> Self {
+        let mut text = format!("SCORE {:.1}", report.score());
+        if standing.personal_best {
+            text += "\nNEW PERSONAL BEST!";
+        } else if let Some(rank) = standing.rank {
+            text += &format!("\nRANK #{rank} ON THIS SEED");
+        }
+        if let Some(worst) = report.worst_criterion() {
+            text += &format!("\nWORST: {worst}");
+        }
+        text += &format!("\n{}", report.remark());
+        Self::new(text, 1.0, 1.0, 1.0).with_font_size(SUMMARY_FONT_SIZE)
+    }
+
+    /// Returns a banner for an event that warrants a callout, if any.
+    pub fn callout(event: &Event) -> Option<Self> {
+        let banner = match event {
+            Event::FuelCallout { seconds } => {
+                Self::new(format!("{seconds:.0} SECONDS"), 1.0, 1.0, 0.0)
+            }
+            Event::BingoFuel { .. } => Self::new("BINGO FUEL: LAND OR ABORT", 1.0, 0.5, 0.0),
+            _ => return None,
         };
-        Self::new(text, r, g, b)
+        Some(banner.with_duration(3.0))
+    }
+
+    /// Returns the banner's opacity, having been displayed for `elapsed` seconds.
+    fn alpha(&self, elapsed: f32) -> f64 {
+        let fade_in = elapsed / FADE_DURATION;
+        let fade_out = self
+            .duration
+            .map_or(f32::INFINITY, |d| (d - elapsed) / FADE_DURATION);
+        MAX_ALPHA * f64::from(fade_in.min(fade_out).clamp(0.0, 1.0))
+    }
+
+    fn scene_update(&self, elapsed: f32) -> SceneUpdate {
+        let (r, g, b) = self.color;
+        let a = self.alpha(elapsed);
+        SceneUpdate {
+            entities: vec![SceneEntity {
+                id: "banner".into(),
+                frame_id: "banner".into(),
+                texts: vec![TextPrimitive {
+                    pose: None,
+                    billboard: true,
+                    font_size: self.font_size,
+                    scale_invariant: true,
+                    color: Some(Color { r, g, b, a }),
+                    text: self.text.clone(),
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
     }
 
     pub fn frame_transform(&self) -> FrameTransform {
@@ -54,10 +149,6 @@ impl Banner {
         }
     }
 
-    pub fn log_scene(&self) {
-        BANNER.log(&self.0)
-    }
-
     pub fn clear_scene() {
         BANNER.log(&SceneUpdate {
             deletions: vec![SceneEntityDeletion {
@@ -68,3 +159,119 @@ impl Banner {
         })
     }
 }
+
+/// A queue of banner messages, displayed one after another.
+#[derive(Default)]
+pub struct Banners {
+    queue: VecDeque<Banner>,
+    /// How long the banner at the front of the queue has been displayed, in seconds.
+    elapsed: f32,
+}
+
+impl Banners {
+    /// Queues a banner, to be displayed after any banners already in the queue.
+    pub fn push(&mut self, banner: Banner) {
+        self.queue.push_back(banner);
+    }
+
+    /// Removes all banners from the queue.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.elapsed = 0.0;
+    }
+
+    /// Returns the banner currently being displayed, if any.
+    pub fn current(&self) -> Option<&Banner> {
+        self.queue.front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Advances the queue by `dt` seconds, moving on from any banners that have expired.
+    pub fn update(&mut self, dt: f32) {
+        self.elapsed += dt;
+        while let Some(banner) = self.queue.front() {
+            let expired = match banner.duration {
+                Some(duration) => self.elapsed >= duration,
+                None => self.queue.len() > 1,
+            };
+            if !expired {
+                break;
+            }
+            self.queue.pop_front();
+            self.elapsed = 0.0;
+        }
+    }
+
+    /// Logs the banner currently being displayed, or clears the banner if there is none.
+    pub fn log_scene(&self) {
+        match self.current() {
+            Some(banner) => BANNER.log(&banner.scene_update(self.elapsed)),
+            None => Banner::clear_scene(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(banners: &Banners) -> Option<&str> {
+        banners.current().map(|banner| banner.text.as_str())
+    }
+
+    #[test]
+    fn test_countdown_expires_in_order() {
+        let mut banners = Banners::default();
+        for banner in Banner::countdown() {
+            banners.push(banner);
+        }
+        assert_eq!(text(&banners), Some("3"));
+        banners.update(0.5);
+        assert_eq!(text(&banners), Some("3"));
+        banners.update(0.5);
+        assert_eq!(text(&banners), Some("2"));
+        banners.update(1.0);
+        assert_eq!(text(&banners), Some("1"));
+        banners.update(1.0);
+        assert!(banners.is_empty());
+    }
+
+    #[test]
+    fn test_banner_without_duration_waits_for_next() {
+        let mut banners = Banners::default();
+        banners.push(Banner::press_start());
+        banners.update(100.0);
+        assert_eq!(text(&banners), Some("PRESS START"));
+        banners.push(Banner::callout(&Event::FuelCallout { seconds: 60.0 }).unwrap());
+        banners.update(0.1);
+        assert_eq!(text(&banners), Some("60 SECONDS"));
+        // The callout is displayed for its full duration, from when it reached the front.
+        banners.update(2.8);
+        assert_eq!(text(&banners), Some("60 SECONDS"));
+        banners.update(0.3);
+        assert!(banners.is_empty());
+    }
+
+    #[test]
+    fn test_clear_resets_queue() {
+        let mut banners = Banners::default();
+        banners.push(Banner::landing_status(LandingStatus::Landed));
+        banners.update(1.5);
+        banners.clear();
+        banners.push(Banner::landing_status(LandingStatus::Crashed));
+        banners.update(1.5);
+        assert_eq!(text(&banners), Some("YOU DIED"));
+    }
+
+    #[test]
+    fn test_alpha_fades_in_and_out() {
+        let banner = Banner::callout(&Event::BingoFuel { fuel_mass: 100.0 }).unwrap();
+        assert_eq!(banner.alpha(0.0), 0.0);
+        assert_eq!(banner.alpha(1.5), MAX_ALPHA);
+        assert_eq!(banner.alpha(3.0), 0.0);
+        assert_eq!(Banner::press_start().alpha(1000.0), MAX_ALPHA);
+    }
+}
@@ -0,0 +1,267 @@
+use glam::Vec3;
+use noise::{NoiseFn, Perlin};
+use rand::prelude::*;
+
+/// Names of the bodies in the catalog, indexed by the value of the body parameter.
+pub const BODY_NAMES: [&str; 3] = ["moon", "mars", "asteroid"];
+
+/// Shape of the generated terrain.
+#[derive(Debug)]
+pub struct TerrainStyle {
+    /// Spatial frequency of the largest terrain features, in cycles per meter.
+    pub noise_scale: f64,
+    /// Height of the terrain, relative to the terrain roughness parameter.
+    pub relief: f64,
+    /// Number of layers of finer detail. Each layer doubles the frequency and halves the height.
+    pub octaves: u32,
+}
+
+/// An atmosphere, with an exponential density profile and gusting wind.
+#[derive(Debug)]
+pub struct Atmosphere {
+    /// Air density at the surface, in kg/m^3.
+    pub surface_density: f32,
+    /// Altitude over which the density falls by a factor of e, in meters.
+    pub scale_height: f32,
+    /// Mean wind speed, in meters/s.
+    pub wind_speed: f32,
+    /// Peak speed of gusts above the mean wind, in meters/s.
+    pub gust_speed: f32,
+    /// Typical duration of a gust, in seconds.
+    pub gust_period: f32,
+}
+
+impl Atmosphere {
+    /// Air density at the given altitude, in kg/m^3.
+    pub fn density(&self, altitude: f32) -> f32 {
+        self.surface_density * (-altitude.max(0.0) / self.scale_height).exp()
+    }
+}
+
+/// Where the powered descent starts on a body.
+#[derive(Debug)]
+pub struct PoweredDescent {
+    /// Altitude above the surface, in meters.
+    pub altitude: f32,
+    /// Distance to the landing zone along the surface, in meters.
+    pub range: f32,
+    /// Horizontal speed, in meters/s, which must be below the speed of a circular orbit.
+    pub speed: f32,
+}
+
+/// A celestial body to land on.
+///
+/// Gravity and the start of the powered descent are the defaults for the corresponding
+/// parameters, which are updated when the body is selected. The rest is fixed for each body.
+#[derive(Debug)]
+pub struct Body {
+    pub name: &'static str,
+    /// Downward gravitational acceleration at the surface, in meters/s^2.
+    pub gravity: f32,
+    /// Mean radius, in meters.
+    pub radius: f32,
+    pub atmosphere: Option<Atmosphere>,
+    pub terrain: TerrainStyle,
+    pub powered_descent: PoweredDescent,
+}
+
+/// The body catalog, in the same order as `BODY_NAMES`.
+pub static BODIES: [Body; 3] = [
+    // The Moon, which has no atmosphere, and gently rolling terrain.
+    Body {
+        name: BODY_NAMES[0],
+        gravity: 1.62,
+        radius: 1_737_400.0,
+        atmosphere: None,
+        terrain: TerrainStyle {
+            noise_scale: 0.1,
+            relief: 1.0,
+            octaves: 1,
+        },
+        powered_descent: PoweredDescent {
+            altitude: 3000.0,
+            range: 15_000.0,
+            speed: 300.0,
+        },
+    },
+    // Mars, with a thin atmosphere that's barely enough to slow the lander, but enough to push it
+    // around when the wind picks up. The terrain is made up of broad dunes with ripples on them.
+    Body {
+        name: BODY_NAMES[1],
+        gravity: 3.71,
+        radius: 3_389_500.0,
+        atmosphere: Some(Atmosphere {
+            surface_density: 0.020,
+            scale_height: 11_100.0,
+            wind_speed: 5.0,
+            gust_speed: 15.0,
+            gust_period: 8.0,
+        }),
+        terrain: TerrainStyle {
+            noise_scale: 0.04,
+            relief: 1.5,
+            octaves: 3,
+        },
+        powered_descent: PoweredDescent {
+            altitude: 3000.0,
+            range: 15_000.0,
+            speed: 300.0,
+        },
+    },
+    // A small asteroid, where the lander weighs next to nothing, and the surface is strewn with
+    // boulders. Its orbital speed is only about 30 m/s, so the powered descent starts slow and
+    // close in.
+    Body {
+        name: BODY_NAMES[2],
+        gravity: 0.05,
+        radius: 20_000.0,
+        atmosphere: None,
+        terrain: TerrainStyle {
+            noise_scale: 0.15,
+            relief: 2.0,
+            octaves: 4,
+        },
+        powered_descent: PoweredDescent {
+            altitude: 1000.0,
+            range: 3000.0,
+            speed: 20.0,
+        },
+    },
+];
+
+impl Body {
+    /// Returns the unit vector pointing away from the body's center at `position`.
+    ///
+    /// Positions are relative to the landing zone, on the surface of a sphere with the body's
+    /// radius.
+    pub fn up(&self, position: Vec3) -> Vec3 {
+        (position + Vec3::Z * self.radius).normalize()
+    }
+
+    /// Returns the altitude of `position` above the surface of the sphere.
+    pub fn altitude(&self, position: Vec3) -> f32 {
+        // Equivalent to |p + R| - R, rearranged to avoid cancellation when the altitude is small
+        // compared to the radius.
+        let r = self.radius;
+        let distance = (position + Vec3::Z * r).length();
+        (position.length_squared() + 2.0 * r * position.z) / (distance + r)
+    }
+
+    /// Returns the distance along the surface from the landing zone to the point below
+    /// `position`.
+    pub fn range(&self, position: Vec3) -> f32 {
+        let r = self.radius;
+        r * position.truncate().length().atan2(r + position.z)
+    }
+
+    /// Returns the height of the surface below the landing zone's horizon, at a horizontal
+    /// distance of `distance` from the landing zone.
+    pub fn surface_drop(&self, distance: f32) -> f32 {
+        let r = self.radius;
+        distance * distance / (r + (r * r - distance * distance).max(0.0).sqrt())
+    }
+}
+
+/// Wind blowing across the landing site.
+///
+/// The wind blows from a random direction at the mean speed, with gusts that vary smoothly over
+/// time. Gusts are seeded from the game seed. Bodies without an atmosphere have no wind.
+pub struct Wind {
+    atmosphere: Option<&'static Atmosphere>,
+    direction: f32,
+    gusts: Perlin,
+    elapsed: f32,
+}
+
+impl Wind {
+    pub fn new<R: Rng>(rng: &mut R, body: &'static Body) -> Self {
+        Self {
+            atmosphere: body.atmosphere.as_ref(),
+            direction: rng.random_range(0.0..std::f32::consts::TAU),
+            gusts: Perlin::new(rng.random()),
+            elapsed: 0.0,
+        }
+    }
+
+    /// Advances the wind by `dt`, and returns the wind velocity.
+    pub fn update(&mut self, dt: f32) -> Vec3 {
+        self.elapsed += dt;
+        let Some(atmosphere) = self.atmosphere else {
+            return Vec3::ZERO;
+        };
+        // Sample the noise at two points, for the strength of the gust and how much it veers off
+        // the prevailing direction.
+        let t = f64::from(self.elapsed / atmosphere.gust_period);
+        let gust = self.gusts.get([t, 0.0]).max(0.0) as f32;
+        let veer = self.gusts.get([t, 10.0]) as f32;
+        let speed = atmosphere.wind_speed + gust * atmosphere.gust_speed;
+        let direction = self.direction + veer * std::f32::consts::FRAC_PI_4;
+        Vec3::new(direction.cos(), direction.sin(), 0.0) * speed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f64, tolerance: f64) {
+        assert!(
+            (f64::from(actual) - expected).abs() <= tolerance,
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_altitude() {
+        for body in &BODIES {
+            let r = f64::from(body.radius);
+            // Close to the landing zone, the altitude is the height above it, to the millimeter.
+            assert_close(body.altitude(Vec3::Z * 1.0), 1.0, 1e-3);
+            assert_close(body.altitude(Vec3::new(3.0, 4.0, 2.0)), 2.0, 1e-3);
+            // Far away, the surface has curved away below the landing zone's horizon.
+            let x = 15_000.0;
+            let expected = (r * r + x * x).sqrt() - r;
+            assert_close(body.altitude(Vec3::X * x as f32), expected, expected * 1e-4);
+            // Points on the surface are at zero altitude, all the way around.
+            for angle in [0.001f32, 0.5, 1.5, 3.0] {
+                let surface = Vec3::new(angle.sin(), 0.0, angle.cos() - 1.0) * body.radius;
+                assert_close(body.altitude(surface), 0.0, r * 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_range() {
+        for body in &BODIES {
+            let r = f64::from(body.radius);
+            assert_close(body.range(Vec3::new(3.0, 4.0, 0.0)), 5.0, 1e-3);
+            // The range is along the surface, regardless of altitude.
+            for angle in [0.01f64, 0.5, 1.5, 3.0] {
+                let up = Vec3::new(angle.sin() as f32, 0.0, angle.cos() as f32);
+                let position = up * (body.radius + 1000.0) - Vec3::Z * body.radius;
+                assert_close(body.range(position), r * angle, r * 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_surface_drop() {
+        for body in &BODIES {
+            let r = f64::from(body.radius);
+            assert_eq!(body.surface_drop(0.0), 0.0);
+            for distance in [10.0f64, 1000.0, 15_000.0] {
+                let expected = r - (r * r - distance * distance).sqrt();
+                assert_close(
+                    body.surface_drop(distance as f32),
+                    expected,
+                    expected * 1e-4,
+                );
+                // The surface is at zero altitude.
+                let surface = Vec3::new(distance as f32, 0.0, -body.surface_drop(distance as f32));
+                assert_close(body.altitude(surface), 0.0, 1e-3);
+            }
+            // Beyond the radius, the drop is capped at the radius.
+            assert_close(body.surface_drop(body.radius * 2.0), r * 4.0, r * 1e-6);
+        }
+    }
+}
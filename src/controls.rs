@@ -21,6 +21,7 @@ impl Controls {
     pub fn update_from_msg(&self, msg: &GamepadMsg) {
         let mut state = self.state.write();
         state.reset.update(self.gamepad.read_start(msg));
+        state.abort.update(self.gamepad.read_abort(msg));
         state.strafe = Vec2 {
             x: self.gamepad.read_strafe_x(msg),
             y: self.gamepad.read_strafe_y(msg),
@@ -65,6 +66,10 @@ impl Controls {
         self.state.read().reset.get() > 0
     }
 
+    pub fn get_abort_requested(&self) -> bool {
+        self.state.read().abort.get() > 0
+    }
+
     /// Resets all values and button-press state.
     pub fn hard_reset(&self) {
         self.reset(true);
@@ -78,6 +83,7 @@ impl Controls {
     fn reset(&self, hard: bool) {
         let mut inner = self.state.write();
         inner.reset.reset(hard);
+        inner.abort.reset(hard);
         inner.strafe = Vec2::ZERO;
         inner.rotate = Vec3::ZERO;
         inner.vertical_velocity_up.reset(hard);
@@ -87,6 +93,7 @@ impl Controls {
 
 struct State {
     reset: Button,
+    abort: Button,
     strafe: Vec2,
     rotate: Vec3,
     vertical_velocity_up: Button,
@@ -97,6 +104,7 @@ impl Default for State {
         let repeat = Duration::from_millis(100);
         Self {
             reset: Button::default(),
+            abort: Button::default(),
             strafe: Vec2::default(),
             rotate: Vec3::default(),
             vertical_velocity_up: Button::with_repeater(repeat),
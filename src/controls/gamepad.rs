@@ -24,6 +24,9 @@ struct GamepadMap {
     button_vertical_velocity_up: usize,
     button_vertical_velocity_down: usize,
     button_start: usize,
+    /// Configs written before aborts were added don't have an abort button.
+    #[serde(default)]
+    button_abort: Option<usize>,
 }
 
 impl Gamepad {
@@ -81,6 +84,12 @@ impl Gamepad {
     pub fn read_start(&self, msg: &GamepadMsg) -> bool {
         msg.read_button(self.map.button_start)
     }
+
+    pub fn read_abort(&self, msg: &GamepadMsg) -> bool {
+        self.map
+            .button_abort
+            .is_some_and(|idx| msg.read_button(idx))
+    }
 }
 
 /// A message containing gamepad state.
@@ -1,4 +1,4 @@
-use foxglove::schemas::{Quaternion, Vector3};
+use foxglove::schemas::{Point3, Quaternion, Vector3};
 
 pub trait IntoFg<T> {
     fn into_fg(self) -> T;
@@ -12,6 +12,15 @@ impl IntoFg<Vector3> for glam::Vec3 {
         }
     }
 }
+impl IntoFg<Point3> for glam::Vec3 {
+    fn into_fg(self) -> Point3 {
+        Point3 {
+            x: self.x.into(),
+            y: self.y.into(),
+            z: self.z.into(),
+        }
+    }
+}
 impl IntoFg<Quaternion> for glam::Quat {
     fn into_fg(self) -> Quaternion {
         Quaternion {
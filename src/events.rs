@@ -0,0 +1,62 @@
+use foxglove::LazyChannel;
+use serde::Serialize;
+
+use crate::faults::Fault;
+use crate::landing::LandingStatus;
+
+static EVENTS: LazyChannel<EventMsg> = LazyChannel::new("/events");
+
+#[derive(Serialize, schemars::JsonSchema)]
+struct EventMsg<'a> {
+    /// Mission elapsed time, in seconds.
+    mission_time: f64,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+/// A notable moment during a game.
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum Event {
+    /// The game has started.
+    GameStart { seed: u64 },
+    /// The descent stage is running low on fuel, as of the first fuel callout.
+    LowFuel { fuel_mass: f32 },
+    /// The descent stage has this many seconds of fuel remaining at hover throttle.
+    FuelCallout { seconds: f32 },
+    /// The descent stage is almost out of fuel: land or abort now.
+    BingoFuel { fuel_mass: f32 },
+    /// The landing probes have touched the surface.
+    ContactLight,
+    /// The lander has touched down.
+    Touchdown { status: LandingStatus, score: f32 },
+    /// The landing was aborted, and the descent stage staged off.
+    Abort,
+    /// The ascent stage has climbed back up, completing an abort.
+    AbortComplete { score: f32 },
+    /// The scenario's time limit ran out before the lander landed.
+    TimeExpired,
+    /// The game was reset.
+    Reset,
+    /// A fault was injected.
+    Fault(Fault),
+}
+
+impl Event {
+    /// Logs the event.
+    pub fn log(&self, mission_time: f32) {
+        EVENTS.log(&self.to_msg(mission_time));
+    }
+
+    /// Returns the event as a JSON string, for inclusion in recording metadata.
+    pub fn to_json(&self, mission_time: f32) -> String {
+        serde_json::to_string(&self.to_msg(mission_time)).expect("serializable")
+    }
+
+    fn to_msg(&self, mission_time: f32) -> EventMsg<'_> {
+        EventMsg {
+            mission_time: mission_time.into(),
+            event: self,
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use glam::Vec3;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+use crate::parameters::Parameters;
+
+/// Rotation command produced by a stuck RCS thruster pair, as a fraction of full command.
+const STUCK_THRUSTER_COMMAND: f32 = 0.5;
+
+/// A kind of fault.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FaultKind {
+    EngineOut,
+    StuckThruster,
+    RadarFreeze,
+    FuelLeak,
+}
+
+/// A fault that has been injected into the simulation.
+#[derive(Debug, Clone, Copy, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case", tag = "fault")]
+pub enum Fault {
+    /// The descent engine has failed, and will not throttle up again.
+    EngineOut,
+    /// An RCS thruster pair is stuck on, producing a constant rotation command.
+    StuckThruster { rotate: [f32; 3] },
+    /// The landing radar is frozen, and repeats its last measurement.
+    RadarFreeze,
+    /// The descent stage is leaking fuel at a constant rate, in kg/s.
+    FuelLeak { rate: f32 },
+}
+
+/// Injects faults into the simulation, on a schedule or at random.
+///
+/// Each kind of fault occurs at most once per game. Random faults are seeded from the game seed.
+pub struct Faults {
+    rng: ChaCha8Rng,
+    elapsed: f32,
+    schedule: Vec<(f32, FaultKind)>,
+    random_rate: f32,
+    fuel_leak_rate: f32,
+    triggered: Vec<FaultKind>,
+}
+
+impl Faults {
+    pub fn new<R: Rng>(rng: &mut R, params: &Parameters) -> Self {
+        let schedule = [
+            (params.fault_engine_out_time(), FaultKind::EngineOut),
+            (params.fault_stuck_thruster_time(), FaultKind::StuckThruster),
+            (params.fault_radar_freeze_time(), FaultKind::RadarFreeze),
+            (params.fault_fuel_leak_time(), FaultKind::FuelLeak),
+        ]
+        .into_iter()
+        .filter(|(time, _)| *time > 0.0)
+        .collect();
+        Self {
+            rng: ChaCha8Rng::from_rng(rng),
+            elapsed: 0.0,
+            schedule,
+            random_rate: params.fault_random_rate(),
+            fuel_leak_rate: params.fault_fuel_leak_rate(),
+            triggered: vec![],
+        }
+    }
+
+    /// Advances the fault clock, and returns any faults that occur during this step.
+    pub fn update(&mut self, dt: f32) -> Vec<Fault> {
+        self.elapsed += dt;
+        let mut kinds: Vec<_> = self
+            .schedule
+            .iter()
+            .filter(|(time, _)| *time <= self.elapsed)
+            .map(|(_, kind)| *kind)
+            .collect();
+        self.schedule.retain(|(time, _)| *time > self.elapsed);
+
+        // Random faults arrive as a Poisson process, at a rate specified per minute.
+        if self.rng.random::<f32>() < self.random_rate / 60.0 * dt {
+            let candidates: Vec<_> = [
+                FaultKind::EngineOut,
+                FaultKind::StuckThruster,
+                FaultKind::RadarFreeze,
+                FaultKind::FuelLeak,
+            ]
+            .into_iter()
+            .filter(|k| !self.triggered.contains(k) && !kinds.contains(k))
+            .collect();
+            if let Some(kind) = candidates.choose(&mut self.rng) {
+                kinds.push(*kind);
+            }
+        }
+
+        kinds.retain(|kind| !self.triggered.contains(kind));
+        self.triggered.extend(&kinds);
+        kinds.into_iter().map(|kind| self.create(kind)).collect()
+    }
+
+    fn create(&mut self, kind: FaultKind) -> Fault {
+        match kind {
+            FaultKind::EngineOut => Fault::EngineOut,
+            FaultKind::StuckThruster => {
+                let axis = [Vec3::X, Vec3::Y, Vec3::Z]
+                    .choose(&mut self.rng)
+                    .copied()
+                    .unwrap();
+                let sign = if self.rng.random() { 1.0 } else { -1.0 };
+                Fault::StuckThruster {
+                    rotate: (axis * sign * STUCK_THRUSTER_COMMAND).to_array(),
+                }
+            }
+            FaultKind::RadarFreeze => Fault::RadarFreeze,
+            FaultKind::FuelLeak => Fault::FuelLeak {
+                rate: self.fuel_leak_rate,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 30.0;
+
+    fn faults(seed: u64, schedule: Vec<(f32, FaultKind)>, random_rate: f32) -> Faults {
+        Faults {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            elapsed: 0.0,
+            schedule,
+            random_rate,
+            fuel_leak_rate: 2.0,
+            triggered: vec![],
+        }
+    }
+
+    fn kind(fault: &Fault) -> FaultKind {
+        match fault {
+            Fault::EngineOut => FaultKind::EngineOut,
+            Fault::StuckThruster { .. } => FaultKind::StuckThruster,
+            Fault::RadarFreeze => FaultKind::RadarFreeze,
+            Fault::FuelLeak { .. } => FaultKind::FuelLeak,
+        }
+    }
+
+    /// Runs the faults for two minutes, and returns each fault with the time it occurred.
+    fn run(faults: &mut Faults) -> Vec<(f32, Fault)> {
+        let mut occurred = vec![];
+        for step in 1..=3600 {
+            for fault in faults.update(DT) {
+                occurred.push((step as f32 * DT, fault));
+            }
+        }
+        occurred
+    }
+
+    /// Asserts that a fault occurred in the step after its scheduled time, allowing for rounding
+    /// in the fault clock.
+    fn assert_on_time(time: f32, scheduled: f32) {
+        assert!(time > scheduled - 0.5 * DT, "{time} is before {scheduled}");
+        assert!(time < scheduled + 1.5 * DT, "{time} is after {scheduled}");
+    }
+
+    #[test]
+    fn test_scheduled_faults_fire_once_on_time() {
+        let schedule = vec![(1.0, FaultKind::EngineOut), (2.5, FaultKind::FuelLeak)];
+        let occurred = run(&mut faults(1, schedule, 0.0));
+        assert_eq!(occurred.len(), 2);
+        let (time, fault) = occurred[0];
+        assert_eq!(kind(&fault), FaultKind::EngineOut);
+        assert_on_time(time, 1.0);
+        let (time, fault) = occurred[1];
+        assert!(matches!(fault, Fault::FuelLeak { rate: 2.0 }));
+        assert_on_time(time, 2.5);
+    }
+
+    #[test]
+    fn test_random_faults_fire_once_per_kind() {
+        // A scheduled fault that has already occurred at random doesn't occur again.
+        let schedule = vec![(30.0, FaultKind::RadarFreeze)];
+        let occurred = run(&mut faults(2, schedule, 60.0));
+        let kinds: Vec<_> = occurred.iter().map(|(_, fault)| kind(fault)).collect();
+        for expected in [
+            FaultKind::EngineOut,
+            FaultKind::StuckThruster,
+            FaultKind::RadarFreeze,
+            FaultKind::FuelLeak,
+        ] {
+            let count = kinds.iter().filter(|k| **k == expected).count();
+            assert_eq!(count, 1, "{expected:?} occurred {count} times");
+        }
+        for (_, fault) in &occurred {
+            if let Fault::StuckThruster { rotate } = fault {
+                let command: f32 = rotate.iter().map(|r| r.abs()).sum();
+                assert_eq!(command, STUCK_THRUSTER_COMMAND);
+            }
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_faults() {
+        let occurred = |seed| format!("{:?}", run(&mut faults(seed, vec![], 10.0)));
+        assert_eq!(occurred(3), occurred(3));
+        assert_ne!(occurred(3), occurred(4));
+    }
+}
@@ -1,22 +1,43 @@
 use foxglove::LazyChannel;
-use foxglove::schemas::{
-    FrameTransform, ModelPrimitive, Pose, Quaternion, SceneEntity, SceneUpdate, Vector3,
-};
+use foxglove::schemas::{FrameTransform, Quaternion, SceneUpdate, Vector3};
 use glam::{EulerRot, Quat, Vec2, Vec3};
 use serde::Serialize;
 
 use crate::controls::Controls;
 use crate::convert::IntoFg;
-use crate::landing::{LandingCriterion, LandingReport};
+use crate::events::Event;
+use crate::faults::Fault;
+use crate::landing::{LandingCriterion, LandingLimits, LandingReport, ScoringModel};
+use crate::landscape::Landscape;
+use crate::sensors::StateEstimate;
 
+mod config;
 mod controllers;
+mod effects;
+mod fuel;
+mod hud;
+mod initial;
+mod prediction;
+mod shadow;
+mod staging;
+mod vehicle;
+pub use config::LanderConfig;
 use controllers::VerticalVelocityController;
+pub use fuel::FuelCallouts;
+use hud::HudLine;
+pub use initial::InitialState;
+pub use prediction::Prediction;
+use staging::DescentStage;
+pub use vehicle::{VEHICLE_NAMES, VEHICLES, Vehicle};
 
 #[derive(Serialize, schemars::JsonSchema)]
 struct LanderMetrics {
     altitude: f64,
     fuel_mass: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fuel_time_remaining: Option<f64>,
     vertical_velocity_target: f64,
+    range_to_go: f64,
 }
 
 static LANDER: LazyChannel<SceneUpdate> = LazyChannel::new("/lander");
@@ -35,139 +56,311 @@ fn init_channels() {
     LANDER_VELOCITY.init();
 }
 
-/// Base mass for the apollo lander.
-const APOLLO_LANDER_DRY_MASS_KG: f32 = 2_150.0;
-
-/// The payload is the ascent stage & ascent fuel.
-const APOLLO_LANDER_PAYLOAD_MASS_KG: f32 = 2_150.0 + 2_400.0;
-
-/// Descent fuel mass.
-///
-/// The initial fuel mass was 8,200 kg, but a good portion of that was spent slowing the descent
-/// from 15km. Our simulator picks up at 200m, so let's suppose there's 600kg of fuel left to make
-/// for a nail-biting final approach.
-const APOLLO_LANDER_INITIAL_FUEL_MASS_KG: f32 = 600.0;
-
-/// Main descent thrust power in newtons.
-const APOLLO_LANDER_DCS_THRUST_N: f32 = 45_000.0;
-
-/// Descent fuel burn rate at full thrust, in kg/s.
-const APOLLO_LANDER_FUEL_BURN_RATE_KGPS: f32 = 15.0;
-
-/// RCS thrust for strafing.
-///
-/// The module had sixteen 440N thrusters arranged in quads. For any direction, there are two
-/// thrusters to use.
-const APOLLO_LANDER_RCS_THRUST_N: f32 = 880.0;
-
-/// RCS torque in newton-meters.
-///
-/// The module had sixteen 440N thrusters arranged in quads, and we're estimating that they're
-/// about 2m from the center of mass.
-const APOLLO_LANDER_RCS_TORQUE_NM: f32 = 3700.0;
-
-/// Estimated inertial profile for the lunar lander.
-///
-/// The lander is roughly round in the horizontal plane with a diameter of 4.2m, and it's 7m
-/// tall with both ascent & descent stages.
-///
-/// If we model it as a cylinder, the moments of inertia are:
-const APOLLO_LANDER_INERTIA: Vec3 = Vec3 {
-    x: (3.0 * 2.1 * 2.1 + 7.0 * 7.0) / 12.0,
-    y: (3.0 * 2.1 * 2.1 + 7.0 * 7.0) / 12.0,
-    z: (2.1 * 2.1) / 2.0,
-};
-
-/// Moon gravitational constant in meters/s^2.
-const MOON_GRAVITY: f32 = -1.62;
+/// Integration step for trajectory prediction, in seconds.
+const PREDICTION_STEP_S: f32 = 0.1;
 
+/// Maximum number of integration steps for trajectory prediction.
+const PREDICTION_MAX_STEPS: usize = 1200;
+
+/// Number of integration steps between points on the predicted trajectory.
+const PREDICTION_SAMPLE_STEPS: usize = 5;
+
+/// Drag coefficient of the lander, which is a blunt body whichever way the air hits it.
+const DRAG_COEFFICIENT: f32 = 1.2;
+
+/// Control inputs applied to the lander during a step.
+#[derive(Debug, Default, Clone, Copy)]
+struct Inputs {
+    /// Main engine throttle [0.0, 1.0].
+    throttle: f32,
+    /// RCS strafe command.
+    strafe: Vec2,
+    /// RCS rotation command.
+    rotate: Vec3,
+}
+
+#[derive(Clone)]
 pub struct Lander {
     position: Vec3,
     velocity: Vec3,
     rotation: Quat,
     angular_velocity: Vec3,
+    config: LanderConfig,
     dry_mass: f32,
     payload_mass: f32,
     fuel_mass: f32,
-    dcs_thrust: f32,
+    engine_thrust: f32,
+    fuel_burn_rate: f32,
     rcs_thrust: f32,
     rcs_torque: f32,
-    landing_zone_radius: u32,
+    init_fuel_mass: f32,
+    landing_limits: LandingLimits,
     vertical_velocity_controller: VerticalVelocityController,
+    inputs: Inputs,
+    specific_force: Vec3,
+    engine_failed: bool,
+    stuck_rotate: Vec3,
+    fuel_leak_rate: f32,
+    fuel_callouts: FuelCallouts,
+    contact_light: bool,
+    init_altitude: f32,
+    descent_stage: Option<DescentStage>,
+    wind: Vec3,
 }
 
 impl Lander {
     pub fn new(
-        position: Vec3,
-        vertical_velocity: f32,
-        vertical_velocity_target: f32,
-        landing_zone_radius: u32,
+        initial: InitialState,
+        config: LanderConfig,
+        landing_limits: LandingLimits,
+        fuel_callouts: FuelCallouts,
     ) -> Self {
         init_channels();
+        let init_altitude = config.body.altitude(initial.position);
         Self {
-            position,
-            velocity: Vec3::Z * vertical_velocity,
-            rotation: Quat::IDENTITY,
-            angular_velocity: Vec3::ZERO,
-            dry_mass: APOLLO_LANDER_DRY_MASS_KG,
-            payload_mass: APOLLO_LANDER_PAYLOAD_MASS_KG,
-            fuel_mass: APOLLO_LANDER_INITIAL_FUEL_MASS_KG,
-            dcs_thrust: APOLLO_LANDER_DCS_THRUST_N,
-            rcs_thrust: APOLLO_LANDER_RCS_THRUST_N,
-            rcs_torque: APOLLO_LANDER_RCS_TORQUE_NM,
-            landing_zone_radius,
+            position: initial.position,
+            velocity: initial.velocity,
+            rotation: initial.rotation,
+            angular_velocity: initial.angular_velocity,
+            dry_mass: config.dry_mass,
+            payload_mass: config.payload_mass(),
+            fuel_mass: initial.fuel_mass,
+            engine_thrust: config.descent_engine_thrust,
+            fuel_burn_rate: config.descent_fuel_burn_rate,
+            rcs_thrust: config.rcs_thrust,
+            rcs_torque: config.rcs_torque,
+            init_fuel_mass: initial.fuel_mass,
+            landing_limits,
             vertical_velocity_controller: VerticalVelocityController::new(
-                vertical_velocity_target,
-                APOLLO_LANDER_DCS_THRUST_N,
+                initial.vertical_velocity_target,
+                config.descent_engine_thrust,
+                config.gravity,
             ),
+            config,
+            inputs: Inputs::default(),
+            specific_force: Vec3::ZERO,
+            engine_failed: false,
+            stuck_rotate: Vec3::ZERO,
+            fuel_leak_rate: 0.0,
+            fuel_callouts,
+            contact_light: false,
+            init_altitude,
+            descent_stage: None,
+            wind: Vec3::ZERO,
         }
     }
 
     pub fn stop(&mut self) {
         self.velocity = Vec3::ZERO;
         self.angular_velocity = Vec3::ZERO;
+        self.inputs = Inputs::default();
+        self.specific_force = Vec3::ZERO;
+    }
+
+    /// Physical properties of the lander, and the body it's landing on.
+    pub fn config(&self) -> &LanderConfig {
+        &self.config
+    }
+
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    pub fn velocity(&self) -> Vec3 {
+        self.velocity
     }
 
-    pub fn step(&mut self, dt: f32, controls: &Controls) {
+    pub fn rotation(&self) -> Quat {
+        self.rotation
+    }
+
+    /// Angular velocity, in body coordinates.
+    pub fn angular_velocity(&self) -> Vec3 {
+        self.angular_velocity
+    }
+
+    /// Non-gravitational acceleration during the last step, in body coordinates.
+    ///
+    /// This is what an accelerometer would measure.
+    pub fn specific_force(&self) -> Vec3 {
+        self.rotation.inverse() * self.specific_force
+    }
+
+    /// Steps the simulation, and returns any events that occurred during the step.
+    ///
+    /// Guidance is computed from the `estimate`, rather than the true state of the lander.
+    pub fn step(&mut self, dt: f32, controls: &Controls, estimate: &StateEstimate) -> Vec<Event> {
+        let mut events = vec![];
+        if controls.get_abort_requested() && !self.has_aborted() {
+            self.abort();
+            events.push(Event::Abort);
+        }
+        if let Some(descent_stage) = &mut self.descent_stage {
+            descent_stage.step(dt);
+        }
+
         // Update target vertical velocity.
         self.vertical_velocity_controller
             .adjust_target(controls.get_and_reset_vertical_velocity_delta());
 
-        let total_mass = self.dry_mass + self.payload_mass + self.fuel_mass;
-        if self.fuel_mass > 0.0 {
-            // Use rate-of-descent PID controller to compute throttle.
-            let throttle = self.vertical_velocity_controller.compute_throttle(
-                self.velocity.z,
-                total_mass,
-                self.tilt(),
+        // Use rate-of-descent PID controller to compute throttle. The ascent engine can't be
+        // throttled, so it burns at full thrust until it runs out of fuel.
+        let throttle = if self.fuel_mass <= 0.0 || self.engine_failed {
+            0.0
+        } else if self.has_aborted() {
+            1.0
+        } else {
+            self.vertical_velocity_controller.compute_throttle(
+                estimate.vertical_speed(self.config.body),
+                self.total_mass(),
+                estimate.tilt(self.config.body),
                 dt,
-            );
+            )
+        };
+
+        self.inputs = Inputs {
+            throttle,
+            strafe: controls.strafe(),
+            rotate: controls.rotate(),
+        };
+        self.integrate(dt, self.inputs);
+        events.extend(self.check_events());
+        events
+    }
 
+    /// Seconds of fuel remaining at the current throttle, or `None` if no fuel is being consumed.
+    pub fn fuel_time_remaining(&self) -> Option<f32> {
+        let rate = self.inputs.throttle * self.fuel_burn_rate + self.fuel_leak_rate;
+        (rate > 0.0).then(|| self.fuel_mass / rate)
+    }
+
+    /// Seconds of fuel remaining at the throttle needed to hover, or `None` if no fuel would be
+    /// consumed.
+    ///
+    /// The fuel callouts are based on this, so that they track how much fuel is left, rather than
+    /// how hard the engine happens to be burning at the moment.
+    fn fuel_time_at_hover(&self) -> Option<f32> {
+        let weight = self.total_mass() * self.config.gravity_at(self.position).length();
+        let throttle = (weight / self.engine_thrust).min(1.0);
+        let rate = throttle * self.fuel_burn_rate + self.fuel_leak_rate;
+        (rate > 0.0).then(|| self.fuel_mass / rate)
+    }
+
+    /// Aborts the landing.
+    ///
+    /// The descent stage is staged off, taking the dry mass and any remaining descent fuel with
+    /// it, and the ascent engine is ignited. Faults in the descent engine and tanks go with it.
+    fn abort(&mut self) {
+        self.descent_stage = Some(DescentStage::separate(
+            self.position,
+            self.velocity,
+            self.rotation,
+            self.config.vehicle.descent_stage,
+            self.config.body,
+            self.config.gravity,
+        ));
+        self.dry_mass = self.config.ascent_stage_dry_mass;
+        self.payload_mass = 0.0;
+        self.fuel_mass = self.config.ascent_stage_fuel_mass;
+        self.engine_thrust = self.config.ascent_engine_thrust;
+        self.fuel_burn_rate = self.config.ascent_fuel_burn_rate;
+        self.engine_failed = false;
+        self.fuel_leak_rate = 0.0;
+    }
+
+    /// Returns true if the landing has been aborted.
+    pub fn has_aborted(&self) -> bool {
+        self.descent_stage.is_some()
+    }
+
+    /// Returns true if the ascent stage has climbed back above the altitude at which the game
+    /// started, completing the abort.
+    fn has_reached_orbit(&self) -> bool {
+        self.has_aborted()
+            && self.altitude() >= self.init_altitude
+            && self.velocity.dot(self.up()) > 0.0
+    }
+
+    /// Returns true if the game is over, either by landing or by completing an abort.
+    pub fn is_finished(&self) -> bool {
+        self.has_landed() || self.has_reached_orbit()
+    }
+
+    /// Returns events for thresholds that were crossed for the first time.
+    fn check_events(&mut self) -> Vec<Event> {
+        let mut events = vec![];
+        if !self.has_aborted()
+            && let Some(time_remaining) = self.fuel_time_at_hover()
+        {
+            events.extend(self.fuel_callouts.check(time_remaining, self.fuel_mass));
+        }
+        if !self.contact_light && self.altitude() <= self.config.vehicle.contact_probe_length {
+            self.contact_light = true;
+            events.push(Event::ContactLight);
+        }
+        events
+    }
+
+    /// Sets the velocity of the wind acting on the lander.
+    ///
+    /// The wind only has an effect on bodies with an atmosphere.
+    pub fn set_wind(&mut self, wind: Vec3) {
+        self.wind = wind;
+    }
+
+    /// Applies a fault to the lander.
+    pub fn apply_fault(&mut self, fault: &Fault) {
+        match fault {
+            Fault::EngineOut => self.engine_failed = true,
+            Fault::StuckThruster { rotate } => self.stuck_rotate += Vec3::from_array(*rotate),
+            Fault::FuelLeak { rate } => self.fuel_leak_rate += rate,
+            Fault::RadarFreeze => (),
+        }
+    }
+
+    /// Advances the physical state of the lander by `dt`, under the given inputs.
+    fn integrate(&mut self, dt: f32, inputs: Inputs) {
+        let total_mass = self.total_mass();
+        let velocity = self.velocity;
+        if self.fuel_mass > 0.0 {
             // Apply throttle.
             let thrust_dir = self.rotation * Vec3::Z;
-            let thrust_force = throttle * thrust_dir * self.dcs_thrust;
+            let thrust_force = inputs.throttle * thrust_dir * self.engine_thrust;
             self.velocity += (thrust_force / total_mass) * dt;
 
             // Consume fuel.
-            let fuel_consumed = throttle * APOLLO_LANDER_FUEL_BURN_RATE_KGPS * dt;
+            let fuel_consumed = inputs.throttle * self.fuel_burn_rate * dt;
             self.fuel_mass = (self.fuel_mass - fuel_consumed).max(0.0);
         }
 
+        // Leak fuel.
+        self.fuel_mass = (self.fuel_mass - self.fuel_leak_rate * dt).max(0.0);
+
         // Apply strafe.
-        let strafe = controls.strafe();
+        let strafe = inputs.strafe;
         let strafe_force = self.rotation * Vec3::new(strafe.x, strafe.y, 0.0) * self.rcs_thrust;
         self.velocity += (strafe_force / total_mass) * dt;
 
-        // Apply gravity.
-        self.velocity += MOON_GRAVITY * Vec3::Z * dt;
+        // Apply drag, from the air moving past the lander.
+        if let Some(atmosphere) = &self.config.body.atmosphere {
+            let airspeed = self.velocity - self.wind;
+            let density = atmosphere.density(self.altitude());
+            let area = self.config.vehicle.frontal_area();
+            let drag = -0.5 * density * DRAG_COEFFICIENT * area * airspeed.length() * airspeed;
+            self.velocity += (drag / total_mass) * dt;
+        }
+        self.specific_force = (self.velocity - velocity) / dt;
+
+        // Apply gravity, towards the center of the body.
+        self.velocity += self.config.gravity_at(self.position) * dt;
 
         // Apply torque.
-        let torque = controls.rotate() * self.rcs_torque;
-        let inertia = total_mass * APOLLO_LANDER_INERTIA;
+        let torque = (inputs.rotate + self.stuck_rotate) * self.rcs_torque;
+        let inertia = total_mass * self.config.vehicle.inertia;
         self.angular_velocity += (torque / inertia) * dt;
 
         // Dampen rotational velocity to make gameplay a bit easier.
-        self.angular_velocity *= 0.999;
+        self.angular_velocity *= self.config.angular_damping;
 
         // Update position & orientation.
         self.position += self.velocity * dt;
@@ -179,41 +372,113 @@ impl Lander {
         );
     }
 
+    /// Predicts the lander's trajectory to touchdown on the terrain.
+    ///
+    /// The prediction holds the current throttle, and assumes that no further RCS inputs are
+    /// applied.
+    pub fn predict(&self, landscape: &Landscape) -> Prediction {
+        let inputs = Inputs {
+            throttle: self.inputs.throttle,
+            ..Default::default()
+        };
+        let mut sim = self.clone();
+        let mut prediction = Prediction::new(sim.position);
+        let mut prev = (sim.position, landscape.height_above_ground(sim.position));
+        for step in 1..=PREDICTION_MAX_STEPS {
+            sim.integrate(PREDICTION_STEP_S, inputs);
+            let height = landscape.height_above_ground(sim.position);
+            if height <= 0.0 {
+                // Interpolate between the last two steps to find where the trajectory meets the
+                // terrain, as the radar's raycast does.
+                let (prev_position, prev_height) = prev;
+                let t = (prev_height / (prev_height - height)).clamp(0.0, 1.0);
+                let position = prev_position.lerp(sim.position, t);
+                let time_to_go = (step as f32 - 1.0 + t) * PREDICTION_STEP_S;
+                prediction.set_touchdown(position, sim.velocity, time_to_go);
+                break;
+            }
+            if step % PREDICTION_SAMPLE_STEPS == 0 {
+                prediction.push(sim.position);
+            }
+            prev = (sim.position, height);
+        }
+        prediction
+    }
+
+    fn total_mass(&self) -> f32 {
+        self.dry_mass + self.payload_mass + self.fuel_mass
+    }
+
+    /// Altitude above the surface of the body, ignoring the terrain.
+    fn altitude(&self) -> f32 {
+        self.config.body.altitude(self.position)
+    }
+
     pub fn has_landed(&self) -> bool {
-        self.position.z <= 0.0
+        self.altitude() <= 0.0
     }
 
-    /// Returns a landing report, if the lander has landed.
-    pub fn landing_report(&self) -> Option<LandingReport> {
-        if self.has_landed() {
-            let criteria = self.landing_criteria();
-            Some(LandingReport::new(criteria))
+    /// Returns a landing report, if the game is over, after `flight_time` seconds.
+    pub fn landing_report(
+        &self,
+        scoring: &ScoringModel,
+        flight_time: f32,
+    ) -> Option<LandingReport> {
+        if self.has_reached_orbit() {
+            Some(LandingReport::aborted(
+                scoring,
+                self.fuel_mass / self.config.ascent_stage_fuel_mass,
+                flight_time,
+            ))
+        } else if self.has_landed() {
+            Some(LandingReport::new(
+                scoring,
+                self.landing_criteria(),
+                self.fuel_mass / self.init_fuel_mass,
+                flight_time,
+            ))
         } else {
             None
         }
     }
 
+    /// Returns true if the lander's rate of descent and attitude are within the landing limits.
+    ///
+    /// This is guidance for the approach, so it leaves out the distance from the target and the
+    /// horizontal speed, which are only expected to be within limits at the very end. The final
+    /// verdict is left to the landing report.
+    pub fn approach_criteria_met(&self) -> bool {
+        let limits = &self.landing_limits;
+        [
+            limits.vertical_speed(self.velocity.dot(self.up()).abs()),
+            limits.tilt(self.tilt()),
+            limits.angular_speed(self.angular_velocity.length()),
+        ]
+        .iter()
+        .all(|c| c.ok())
+    }
+
     /// Returns landing criteria, in order of importance.
     fn landing_criteria(&self) -> Vec<LandingCriterion> {
+        let limits = &self.landing_limits;
         vec![
-            LandingCriterion::vertical_speed(3.0, self.velocity.z.abs()),
-            LandingCriterion::horizontal_speed(
-                1.0,
-                Vec2::new(self.velocity.x, self.velocity.y).length(),
-            ),
-            LandingCriterion::tilt(3.0, self.tilt()),
-            LandingCriterion::angular_speed(0.25, self.angular_velocity.length()),
-            LandingCriterion::distance_from_target(
-                self.landing_zone_radius as f32,
-                self.position.length(),
-            ),
+            limits.vertical_speed(self.velocity.dot(self.up()).abs()),
+            limits.horizontal_speed(self.velocity.reject_from_normalized(self.up()).length()),
+            limits.tilt(self.tilt()),
+            limits.angular_speed(self.angular_velocity.length()),
+            limits.distance_from_target(self.position.truncate().length()),
         ]
     }
 
-    /// Tilt from upright in radians.
+    /// Returns the local vertical at the lander's position.
+    fn up(&self) -> Vec3 {
+        self.config.body.up(self.position)
+    }
+
+    /// Tilt from upright, relative to the local vertical, in radians.
     fn tilt(&self) -> f32 {
         let up = self.rotation * Vec3::Z;
-        up.angle_between(Vec3::Z)
+        up.angle_between(self.up())
     }
 
     pub fn frame_transform(&self) -> FrameTransform {
@@ -226,40 +491,182 @@ impl Lander {
         }
     }
 
-    fn scene_entity(&self) -> SceneEntity {
-        SceneEntity {
-            id: "lander".into(),
-            frame_id: "lander".into(),
-            models: vec![ModelPrimitive {
-                url: "package://foxglove-lunar-lander/assets/apollo.dae".into(),
-                pose: Some(Pose::default()),
-                scale: Some(Vector3 {
-                    x: 1.0,
-                    y: 1.0,
-                    z: 1.0,
-                }),
-                ..Default::default()
-            }],
-            ..Default::default()
-        }
-    }
-
     pub fn log_scene(&self) {
         LANDER.log(&SceneUpdate {
-            entities: vec![self.scene_entity()],
+            entities: vec![self.config.vehicle.scene_entity()],
             deletions: vec![],
         });
+        DescentStage::clear_scene();
+        hud::clear_scene();
+    }
+
+    /// Logs the separated descent stage, if the landing has been aborted.
+    pub fn log_descent_stage(&self) {
+        if let Some(descent_stage) = &self.descent_stage {
+            descent_stage.log_scene();
+        }
+    }
+
+    /// Logs the engine plume, RCS puffs, and dust effects.
+    pub fn log_effects(&self, landscape: &Landscape) {
+        effects::log_plume(self.inputs.throttle);
+        effects::log_rcs(
+            &Inputs {
+                rotate: self.inputs.rotate + self.stuck_rotate,
+                ..self.inputs
+            },
+            self.config.vehicle,
+        );
+        effects::log_dust(self.position, self.inputs.throttle, landscape);
     }
 
-    pub fn log(&self) {
+    /// Logs the lander's shadow, and a drop line to the terrain below.
+    pub fn log_altitude_cues(&self, landscape: &Landscape) {
+        shadow::log_shadow(
+            self.position,
+            self.config.vehicle.footprint_diameter,
+            landscape,
+        );
+        shadow::log_drop_line(self.position, landscape);
+    }
+
+    /// Clears the engine plume, RCS puffs, and dust effects.
+    pub fn clear_effects() {
+        effects::clear_scene();
+    }
+
+    /// Logs a heads-up display next to the lander, as seen by the guidance system.
+    ///
+    /// Values that are subject to landing criteria are colored by whether they'd pass, were the
+    /// lander to touch down now.
+    pub fn log_hud(&self, estimate: &StateEstimate) {
+        let limits = &self.landing_limits;
+        let body = self.config.body;
+        let vertical_speed = estimate.vertical_speed(body);
+        let horizontal_speed = estimate.horizontal_speed(body);
+        let distance = body.range(estimate.position);
+        let tilt = estimate.tilt(body);
+        let fuel = match self.fuel_time_remaining() {
+            Some(t) => format!("FUEL {:.0} kg ({t:.0} s)", self.fuel_mass),
+            None => format!("FUEL {:.0} kg", self.fuel_mass),
+        };
+        let lines = [
+            HudLine::info(format!("ALT {:.1} m", estimate.altitude)),
+            HudLine::limited(
+                format!("V/S {vertical_speed:+.1} m/s"),
+                limits.vertical_speed(vertical_speed.abs()).ok(),
+            ),
+            HudLine::info(format!(
+                "V/S TGT {:+.1} m/s",
+                self.vertical_velocity_controller.target()
+            )),
+            HudLine::limited(
+                format!("H/S {horizontal_speed:.1} m/s"),
+                limits.horizontal_speed(horizontal_speed).ok(),
+            ),
+            HudLine::limited(
+                format!("TILT {:.1}°", tilt.to_degrees()),
+                limits.tilt(tilt).ok(),
+            ),
+            HudLine::limited(
+                if distance < 10_000.0 {
+                    format!("PAD {distance:.0} m")
+                } else {
+                    format!("PAD {:.1} km", distance / 1000.0)
+                },
+                limits.distance_from_target(distance).ok(),
+            ),
+            HudLine::limited(fuel, !self.fuel_callouts.is_bingo()),
+        ];
+        hud::log_hud(self.position, &lines);
+    }
+
+    /// Logs lander telemetry, as seen by the guidance system.
+    pub fn log(&self, estimate: &StateEstimate) {
         LANDER_METRICS.log(&LanderMetrics {
-            altitude: self.position.z.into(),
+            altitude: estimate.altitude.into(),
             fuel_mass: self.fuel_mass.into(),
+            fuel_time_remaining: self.fuel_time_remaining().map(f64::from),
             vertical_velocity_target: self.vertical_velocity_controller.target().into(),
+            range_to_go: self.config.body.range(estimate.position).into(),
         });
-        LANDER_ANGULAR_VELOCITY.log(&self.angular_velocity.into_fg());
-        LANDER_COURSE.log(&(-self.position).into_fg());
-        LANDER_ORIENTATION.log(&self.rotation.into_fg());
-        LANDER_VELOCITY.log(&self.velocity.into_fg());
+        LANDER_ANGULAR_VELOCITY.log(&estimate.angular_velocity.into_fg());
+        LANDER_COURSE.log(&(-estimate.position).into_fg());
+        LANDER_ORIENTATION.log(&estimate.rotation.into_fg());
+        LANDER_VELOCITY.log(&estimate.velocity.into_fg());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::parameters::Parameters;
+
+    fn lander(fuel_mass: f32) -> Lander {
+        let dir = tempfile::tempdir().unwrap();
+        let params =
+            Parameters::load(&dir.path().join("parameters.json"), dir.path(), dir.path()).unwrap();
+        let initial = InitialState {
+            position: Vec3::Z * 100.0,
+            velocity: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            angular_velocity: Vec3::ZERO,
+            vertical_velocity_target: 0.0,
+            fuel_mass,
+        };
+        Lander::new(
+            initial,
+            LanderConfig::new(&params),
+            LandingLimits::new(&params),
+            FuelCallouts::new(params.fuel_callouts(), params.fuel_bingo()),
+        )
+    }
+
+    fn fuel_events(lander: &mut Lander) -> Vec<Event> {
+        lander
+            .check_events()
+            .into_iter()
+            .filter(|e| !matches!(e, Event::ContactLight))
+            .collect()
+    }
+
+    #[test]
+    fn test_full_throttle_burst_makes_no_fuel_callouts() {
+        let mut lander = lander(600.0);
+        lander.inputs.throttle = 1.0;
+        // At full throttle, the fuel would only last 40 s, but there's plenty left to hover.
+        assert!(lander.fuel_time_remaining().unwrap() < 60.0);
+        assert!(fuel_events(&mut lander).is_empty());
+    }
+
+    #[test]
+    fn test_low_fuel_makes_fuel_callouts() {
+        let mut lander = lander(100.0);
+        let events = fuel_events(&mut lander);
+        assert!(matches!(events[0], Event::LowFuel { .. }));
+        assert!(matches!(events[1], Event::FuelCallout { seconds: 60.0 }));
+    }
+
+    #[test]
+    fn test_prediction_touches_down_on_terrain() {
+        let dir = tempfile::tempdir().unwrap();
+        let params =
+            Parameters::load(&dir.path().join("parameters.json"), dir.path(), dir.path()).unwrap();
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let landscape = Landscape::new(&mut rng, &params, None);
+        // Drift off the landing zone, so that the lander comes down on rough terrain.
+        let mut lander = lander(600.0);
+        lander.velocity = Vec3::new(3.0, 1.0, 0.0);
+        let touchdown = lander.predict(&landscape).touchdown_position().unwrap();
+        let ground = landscape.ground_height(touchdown.truncate());
+        // The terrain there is below the body's sphere, where the prediction used to stop short.
+        assert!(params.body().altitude(touchdown).abs() > 0.1);
+        assert!(
+            (touchdown.z - ground).abs() < 0.05,
+            "{touchdown} vs {ground}"
+        );
     }
 }
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use glam::Vec3;
+use serde::{Serialize, Serializer};
+
+use super::vehicle::Vehicle;
+use crate::body::Body;
+use crate::parameters::Parameters;
+
+/// Physical properties of the lander, and the body it's landing on.
+#[derive(Debug, Clone, Serialize)]
+pub struct LanderConfig {
+    /// The vehicle, which determines everything that isn't tunable.
+    #[serde(serialize_with = "serialize_vehicle_name")]
+    pub vehicle: &'static Vehicle,
+    /// The body being landed on, which determines the atmosphere and terrain.
+    #[serde(serialize_with = "serialize_body_name")]
+    pub body: &'static Body,
+    /// Dry mass of the descent stage, in kg.
+    pub dry_mass: f32,
+    /// Dry mass of the ascent stage, in kg.
+    pub ascent_stage_dry_mass: f32,
+    /// Ascent stage fuel mass, in kg.
+    pub ascent_stage_fuel_mass: f32,
+    /// Descent engine thrust, in newtons.
+    pub descent_engine_thrust: f32,
+    /// Descent engine fuel burn rate at full thrust, in kg/s.
+    pub descent_fuel_burn_rate: f32,
+    /// Ascent engine thrust, in newtons.
+    pub ascent_engine_thrust: f32,
+    /// Ascent engine fuel burn rate, in kg/s.
+    pub ascent_fuel_burn_rate: f32,
+    /// RCS thrust for strafing, in newtons.
+    pub rcs_thrust: f32,
+    /// RCS torque, in newton-meters.
+    pub rcs_torque: f32,
+    /// Downward gravitational acceleration, in meters/s^2.
+    pub gravity: f32,
+    /// Fraction of angular velocity retained after each step.
+    pub angular_damping: f32,
+}
+
+impl LanderConfig {
+    pub fn new(params: &Parameters) -> Self {
+        Self {
+            vehicle: params.vehicle(),
+            body: params.body(),
+            dry_mass: params.lander_dry_mass(),
+            ascent_stage_dry_mass: params.ascent_stage_dry_mass(),
+            ascent_stage_fuel_mass: params.ascent_stage_fuel_mass(),
+            descent_engine_thrust: params.descent_engine_thrust(),
+            descent_fuel_burn_rate: params.descent_fuel_burn_rate(),
+            ascent_engine_thrust: params.ascent_engine_thrust(),
+            ascent_fuel_burn_rate: params.ascent_fuel_burn_rate(),
+            rcs_thrust: params.rcs_thrust(),
+            rcs_torque: params.rcs_torque(),
+            gravity: params.gravity(),
+            angular_damping: params.angular_damping(),
+        }
+    }
+
+    /// Gravitational acceleration at `position`, which weakens with altitude.
+    pub fn gravity_at(&self, position: Vec3) -> Vec3 {
+        let scale = self.body.radius / (self.body.radius + self.body.altitude(position));
+        -self.gravity * scale * scale * self.body.up(position)
+    }
+
+    /// Mass of the ascent stage and its fuel, which the descent stage carries as payload.
+    pub fn payload_mass(&self) -> f32 {
+        self.ascent_stage_dry_mass + self.ascent_stage_fuel_mass
+    }
+
+    /// Returns the configuration as MCAP metadata.
+    pub fn to_metadata(&self) -> BTreeMap<String, String> {
+        let serde_json::Value::Object(fields) = serde_json::to_value(self).unwrap() else {
+            unreachable!("config serializes to an object");
+        };
+        fields
+            .into_iter()
+            .map(|(name, value)| match value {
+                serde_json::Value::String(text) => (name, text),
+                value => (name, value.to_string()),
+            })
+            .collect()
+    }
+}
+
+fn serialize_vehicle_name<S: Serializer>(vehicle: &&Vehicle, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(vehicle.name)
+}
+
+fn serialize_body_name<S: Serializer>(body: &&Body, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(body.name)
+}
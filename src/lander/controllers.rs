@@ -1,7 +1,5 @@
-use super::MOON_GRAVITY;
-
 /// Generic PID controller.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct PidController {
     kp: f32,
     ki: f32,
@@ -36,19 +34,22 @@ impl PidController {
 ///
 /// This is intended to be similar to the Apollo lander's rate-of-descent (RoD) controller, but the
 /// PID values are totally fabricated.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct VerticalVelocityController {
     pid: PidController,
     target: f32,
     thrust: f32,
+    /// Downward gravitational acceleration, in meters/s^2.
+    gravity: f32,
 }
 impl VerticalVelocityController {
     /// Creates a new vertical velocity controller.
-    pub fn new(target: f32, thrust: f32) -> Self {
+    pub fn new(target: f32, thrust: f32, gravity: f32) -> Self {
         Self {
             pid: PidController::new(0.8, 0.05, 0.3),
             target,
             thrust,
+            gravity,
         }
     }
 
@@ -69,7 +70,7 @@ impl VerticalVelocityController {
     /// vertical velocity.
     pub fn compute_throttle(&mut self, current: f32, mass: f32, tilt: f32, dt: f32) -> f32 {
         let desired_accel = self.pid.update(self.target, current, dt);
-        let total_vertical_accel = MOON_GRAVITY + desired_accel;
+        let total_vertical_accel = desired_accel - self.gravity;
         let required_vertical_force = mass * total_vertical_accel;
         let throttle = required_vertical_force / (self.thrust * tilt.cos());
         throttle.clamp(0.0, 1.0)
@@ -0,0 +1,246 @@
+use foxglove::LazyChannel;
+use foxglove::schemas::{
+    Color, CylinderPrimitive, Pose, SceneEntity, SceneEntityDeletion, SceneUpdate, SpherePrimitive,
+    Vector3, scene_entity_deletion,
+};
+use glam::{Quat, Vec2, Vec3};
+use rand::prelude::*;
+
+use super::Inputs;
+use super::vehicle::Vehicle;
+use crate::convert::IntoFg;
+use crate::landscape::Landscape;
+
+static LANDER_PLUME: LazyChannel<SceneUpdate> = LazyChannel::new("/lander_plume");
+static LANDER_RCS: LazyChannel<SceneUpdate> = LazyChannel::new("/lander_rcs");
+static LANDER_DUST: LazyChannel<SceneUpdate> = LazyChannel::new("/lander_dust");
+
+/// Height of the descent engine nozzle exit above the lander origin.
+const PLUME_ORIGIN_Z: f32 = 0.5;
+
+/// Length of the descent engine plume at full throttle.
+const PLUME_MAX_LENGTH: f32 = 8.0;
+
+/// Diameter of the descent engine plume at the nozzle exit.
+const PLUME_DIAMETER: f32 = 1.5;
+
+/// Throttle below which the descent engine plume is not rendered.
+const PLUME_MIN_THROTTLE: f32 = 0.01;
+
+/// Command magnitude below which an RCS thruster is considered idle.
+const RCS_MIN_COMMAND: f32 = 0.05;
+
+/// Size of an RCS puff at full command.
+const RCS_PUFF_SIZE: f32 = 0.6;
+
+/// Radar altitude below which the descent engine kicks up dust.
+const DUST_MAX_ALTITUDE: f32 = 15.0;
+
+/// Number of dust particles at full intensity.
+const DUST_MAX_PARTICLES: usize = 60;
+
+/// Radius of the dust cloud at full intensity.
+const DUST_MAX_RADIUS: f32 = 12.0;
+
+fn deletion(id: &str) -> SceneUpdate {
+    SceneUpdate {
+        deletions: vec![SceneEntityDeletion {
+            id: id.into(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+/// Logs the descent engine plume, scaled by throttle.
+pub(super) fn log_plume(throttle: f32) {
+    if throttle < PLUME_MIN_THROTTLE {
+        LANDER_PLUME.log(&deletion("plume"));
+        return;
+    }
+    let length = throttle * PLUME_MAX_LENGTH;
+    let diameter = f64::from(PLUME_DIAMETER);
+    LANDER_PLUME.log(&SceneUpdate {
+        entities: vec![SceneEntity {
+            id: "plume".into(),
+            frame_id: "lander".into(),
+            cylinders: vec![CylinderPrimitive {
+                pose: Some(Pose {
+                    position: Some(Vec3::new(0.0, 0.0, PLUME_ORIGIN_Z - length / 2.0).into_fg()),
+                    ..Default::default()
+                }),
+                size: Some(Vector3 {
+                    x: diameter * 2.0,
+                    y: diameter * 2.0,
+                    z: length.into(),
+                }),
+                bottom_scale: 1.0,
+                top_scale: 0.5,
+                color: Some(Color {
+                    r: 1.0,
+                    g: 0.6,
+                    b: 0.2,
+                    a: (0.2 + 0.4 * throttle).into(),
+                }),
+            }],
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+}
+
+/// Returns a list of (position, exhaust direction, magnitude) for each firing RCS thruster.
+///
+/// The quads are modeled at the ends of the body x and y axes. Positive torque about an axis is
+/// produced by firing vertical jets on the quads along the perpendicular axis, and yaw by
+/// firing tangential jets on all four quads.
+fn rcs_firings(inputs: &Inputs, vehicle: &Vehicle) -> Vec<(Vec3, Vec3, f32)> {
+    let distance = vehicle.rcs_quad_distance;
+    let quad = |x: f32, y: f32| Vec3::new(x * distance, y * distance, vehicle.rcs_quad_z);
+    let mut firings = vec![];
+
+    // Strafe: exhaust opposes the commanded force, from the quads on the trailing side.
+    let strafe = inputs.strafe;
+    if strafe.x.abs() > RCS_MIN_COMMAND {
+        let s = -strafe.x.signum();
+        firings.push((quad(s, 0.0), Vec3::X * s, strafe.x.abs()));
+    }
+    if strafe.y.abs() > RCS_MIN_COMMAND {
+        let s = -strafe.y.signum();
+        firings.push((quad(0.0, s), Vec3::Y * s, strafe.y.abs()));
+    }
+
+    // Pitch (torque about x): quad +y pushes up, quad -y pushes down.
+    let rotate = inputs.rotate;
+    if rotate.x.abs() > RCS_MIN_COMMAND {
+        let s = rotate.x.signum();
+        firings.push((quad(0.0, 1.0), -Vec3::Z * s, rotate.x.abs()));
+        firings.push((quad(0.0, -1.0), Vec3::Z * s, rotate.x.abs()));
+    }
+
+    // Roll (torque about y): quad +x pushes down, quad -x pushes up.
+    if rotate.y.abs() > RCS_MIN_COMMAND {
+        let s = rotate.y.signum();
+        firings.push((quad(1.0, 0.0), Vec3::Z * s, rotate.y.abs()));
+        firings.push((quad(-1.0, 0.0), -Vec3::Z * s, rotate.y.abs()));
+    }
+
+    // Yaw (torque about z): all quads push tangentially.
+    if rotate.z.abs() > RCS_MIN_COMMAND {
+        let s = rotate.z.signum();
+        for (x, y) in [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)] {
+            let tangent = Vec3::new(-y, x, 0.0);
+            firings.push((quad(x, y), -tangent * s, rotate.z.abs()));
+        }
+    }
+
+    firings
+}
+
+/// Logs a puff for each firing RCS thruster.
+pub(super) fn log_rcs(inputs: &Inputs, vehicle: &Vehicle) {
+    let firings = rcs_firings(inputs, vehicle);
+    if firings.is_empty() {
+        LANDER_RCS.log(&deletion("rcs"));
+        return;
+    }
+    LANDER_RCS.log(&SceneUpdate {
+        entities: vec![SceneEntity {
+            id: "rcs".into(),
+            frame_id: "lander".into(),
+            spheres: firings
+                .into_iter()
+                .map(|(position, direction, magnitude)| {
+                    let size = RCS_PUFF_SIZE * magnitude.min(1.0);
+                    SpherePrimitive {
+                        pose: Some(Pose {
+                            position: Some((position + direction * size).into_fg()),
+                            orientation: Some(
+                                Quat::from_rotation_arc(Vec3::X, direction).into_fg(),
+                            ),
+                        }),
+                        size: Some(Vector3 {
+                            x: (size * 2.0).into(),
+                            y: size.into(),
+                            z: size.into(),
+                        }),
+                        color: Some(Color {
+                            r: 1.0,
+                            g: 1.0,
+                            b: 1.0,
+                            a: 0.6,
+                        }),
+                    }
+                })
+                .collect(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+}
+
+/// Logs a dust cloud on the terrain below the lander, when the descent engine is firing at low
+/// altitude.
+pub(super) fn log_dust(position: Vec3, throttle: f32, landscape: &Landscape) {
+    let below = position.truncate();
+    let ground = landscape.ground_height(below);
+    let altitude = position.z - ground;
+    let intensity = throttle * (1.0 - altitude / DUST_MAX_ALTITUDE).min(1.0);
+    if throttle < PLUME_MIN_THROTTLE || intensity <= 0.0 {
+        LANDER_DUST.log(&deletion("dust"));
+        return;
+    }
+
+    let mut rng = rand::rng();
+    let radius = DUST_MAX_RADIUS * intensity.sqrt();
+    let count = (DUST_MAX_PARTICLES as f32 * intensity).ceil() as usize;
+    let particles = (0..count)
+        .map(|_| {
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            let distance = radius * rng.random::<f32>().sqrt();
+            let xy = below + Vec2::from_angle(angle) * distance;
+            let z = landscape.ground_height(xy) + rng.random_range(0.0..0.5);
+            let size = f64::from(rng.random_range(0.2..0.6_f32));
+            SpherePrimitive {
+                pose: Some(Pose {
+                    position: Some(xy.extend(z).into_fg()),
+                    ..Default::default()
+                }),
+                size: Some(Vector3 {
+                    x: size,
+                    y: size,
+                    z: size / 2.0,
+                }),
+                color: Some(Color {
+                    r: 0.7,
+                    g: 0.7,
+                    b: 0.65,
+                    a: (0.3 + 0.4 * intensity).into(),
+                }),
+            }
+        })
+        .collect();
+    LANDER_DUST.log(&SceneUpdate {
+        entities: vec![SceneEntity {
+            id: "dust".into(),
+            frame_id: "landing_zone".into(),
+            spheres: particles,
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+}
+
+/// Clears all effects from the scene.
+pub(super) fn clear_scene() {
+    let clear = SceneUpdate {
+        deletions: vec![SceneEntityDeletion {
+            r#type: scene_entity_deletion::Type::All.into(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    LANDER_PLUME.log(&clear);
+    LANDER_RCS.log(&clear);
+    LANDER_DUST.log(&clear);
+}
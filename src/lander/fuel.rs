@@ -0,0 +1,89 @@
+use crate::events::Event;
+
+/// Apollo-style fuel callouts.
+///
+/// Callouts are made once each, as the seconds of fuel remaining at hover throttle fall below
+/// their thresholds. The bingo fuel callout is the decision point: from there, the crew
+/// must land or abort. Thresholds of zero are disabled.
+///
+/// The first callout is accompanied by a low fuel event, which predates the callouts, for
+/// consumers that only care that fuel is running low.
+#[derive(Debug, Clone)]
+pub struct FuelCallouts {
+    callouts: Vec<(f32, bool)>,
+    bingo: (f32, bool),
+    low_fuel: bool,
+}
+
+impl FuelCallouts {
+    /// Creates fuel callouts for the given thresholds, in seconds of fuel remaining.
+    pub fn new(callouts: impl IntoIterator<Item = f32>, bingo: f32) -> Self {
+        Self {
+            callouts: callouts.into_iter().map(|t| (t, false)).collect(),
+            bingo: (bingo, false),
+            low_fuel: false,
+        }
+    }
+
+    /// Returns true if the bingo fuel callout has been made.
+    pub(super) fn is_bingo(&self) -> bool {
+        self.bingo.1
+    }
+
+    /// Returns events for callouts whose thresholds were crossed for the first time.
+    pub(super) fn check(&mut self, time_remaining: f32, fuel_mass: f32) -> Vec<Event> {
+        let crossed = |(threshold, called): &mut (f32, bool)| {
+            let cross = !*called && *threshold > 0.0 && time_remaining <= *threshold;
+            *called |= cross;
+            cross
+        };
+        let mut events: Vec<_> = self
+            .callouts
+            .iter_mut()
+            .filter_map(|callout| {
+                crossed(callout).then_some(Event::FuelCallout { seconds: callout.0 })
+            })
+            .collect();
+        if !self.low_fuel && !events.is_empty() {
+            self.low_fuel = true;
+            events.insert(0, Event::LowFuel { fuel_mass });
+        }
+        if crossed(&mut self.bingo) {
+            events.push(Event::BingoFuel { fuel_mass });
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(events: &[Event]) -> Vec<String> {
+        events.iter().map(|e| format!("{e:?}")).collect()
+    }
+
+    #[test]
+    fn test_callouts_fire_in_order_once_each() {
+        let mut callouts = FuelCallouts::new([60.0, 30.0, 0.0], 20.0);
+        assert!(callouts.check(90.0, 900.0).is_empty());
+        assert_eq!(
+            names(&callouts.check(59.0, 590.0)),
+            [
+                "LowFuel { fuel_mass: 590.0 }",
+                "FuelCallout { seconds: 60.0 }"
+            ]
+        );
+        assert!(callouts.check(58.0, 580.0).is_empty());
+        // Skipping past several thresholds at once makes each of them, in order.
+        assert_eq!(
+            names(&callouts.check(10.0, 100.0)),
+            [
+                "FuelCallout { seconds: 30.0 }",
+                "BingoFuel { fuel_mass: 100.0 }"
+            ]
+        );
+        assert!(callouts.is_bingo());
+        assert!(callouts.check(0.0, 0.0).is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+use foxglove::LazyChannel;
+use foxglove::schemas::{
+    Color, Pose, SceneEntity, SceneEntityDeletion, SceneUpdate, TextPrimitive,
+};
+use glam::Vec3;
+
+use crate::convert::IntoFg;
+
+static LANDER_HUD: LazyChannel<SceneUpdate> = LazyChannel::new("/lander_hud");
+
+/// Offset of the top line of the HUD from the lander origin, in the landing zone frame.
+const HUD_OFFSET: Vec3 = Vec3::new(6.0, 0.0, 10.0);
+
+/// Height of HUD text, in meters.
+const HUD_FONT_SIZE: f64 = 1.0;
+
+/// Spacing between lines of HUD text, in meters.
+const HUD_LINE_SPACING: f32 = 1.4;
+
+/// Color of HUD lines whose values are within limits.
+const COLOR_OK: (f64, f64, f64) = (0.0, 1.0, 0.0);
+
+/// Color of HUD lines whose values exceed limits.
+const COLOR_EXCEEDED: (f64, f64, f64) = (1.0, 0.0, 0.0);
+
+/// Color of HUD lines that have no limits.
+const COLOR_INFO: (f64, f64, f64) = (1.0, 1.0, 1.0);
+
+/// A line of text on the HUD.
+pub(super) struct HudLine {
+    text: String,
+    /// Whether the value is within limits, if it has any.
+    ok: Option<bool>,
+}
+
+impl HudLine {
+    /// A line with a value that has no limits.
+    pub(super) fn info(text: String) -> Self {
+        Self { text, ok: None }
+    }
+
+    /// A line with a value that is subject to limits.
+    pub(super) fn limited(text: String, ok: bool) -> Self {
+        Self { text, ok: Some(ok) }
+    }
+
+    fn color(&self) -> Color {
+        let (r, g, b) = match self.ok {
+            None => COLOR_INFO,
+            Some(true) => COLOR_OK,
+            Some(false) => COLOR_EXCEEDED,
+        };
+        Color { r, g, b, a: 0.9 }
+    }
+}
+
+/// Logs the HUD next to the lander.
+///
+/// The HUD is positioned in the landing zone frame, rather than the lander frame, so that it
+/// doesn't tumble along with the lander.
+pub(super) fn log_hud(position: Vec3, lines: &[HudLine]) {
+    let top = position + HUD_OFFSET;
+    LANDER_HUD.log(&SceneUpdate {
+        entities: vec![SceneEntity {
+            id: "hud".into(),
+            frame_id: "landing_zone".into(),
+            texts: lines
+                .iter()
+                .enumerate()
+                .map(|(i, line)| TextPrimitive {
+                    pose: Some(Pose {
+                        position: Some((top - Vec3::Z * HUD_LINE_SPACING * i as f32).into_fg()),
+                        ..Default::default()
+                    }),
+                    billboard: true,
+                    font_size: HUD_FONT_SIZE,
+                    scale_invariant: false,
+                    color: Some(line.color()),
+                    text: line.text.clone(),
+                })
+                .collect(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+}
+
+/// Clears the HUD from the scene.
+pub(super) fn clear_scene() {
+    LANDER_HUD.log(&SceneUpdate {
+        deletions: vec![SceneEntityDeletion {
+            id: "hud".into(),
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+}
@@ -0,0 +1,170 @@
+use std::f32::consts::TAU;
+
+use glam::{DVec3, Quat, Vec2, Vec3};
+use rand::prelude::*;
+
+use crate::landscape::Landscape;
+use crate::parameters::{Parameters, VERTICAL_VELOCITY_TARGET_RANGE};
+
+/// The state of the lander at the start of a game.
+///
+/// The horizontal velocity, attitude and angular velocity are perturbed at random, within the
+/// bounds set by the parameters. Perturbations are seeded from the game seed.
+#[derive(Debug, Clone, Copy)]
+pub struct InitialState {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub rotation: Quat,
+    /// Angular velocity, in body coordinates.
+    pub angular_velocity: Vec3,
+    pub vertical_velocity_target: f32,
+    pub fuel_mass: f32,
+}
+
+impl InitialState {
+    pub fn new<R: Rng>(rng: &mut R, params: &Parameters, landscape: &Landscape) -> Self {
+        let mut state = if params.powered_descent() {
+            Self::powered_descent(params, landscape)
+        } else {
+            Self {
+                position: landscape.lander_init_position(),
+                velocity: Vec3::Z * params.lander_init_vertical_velocity(),
+                rotation: Quat::IDENTITY,
+                angular_velocity: Vec3::ZERO,
+                vertical_velocity_target: params.lander_init_vertical_velocity_target(),
+                fuel_mass: params.lander_init_fuel_mass(),
+            }
+        };
+        state.perturb(rng, params);
+        state
+    }
+
+    /// Applies random perturbations to the state.
+    ///
+    /// Perturbations with a bound of zero are skipped without drawing from `rng`, so that games
+    /// without them play out the same as before they were introduced.
+    fn perturb<R: Rng>(&mut self, rng: &mut R, params: &Parameters) {
+        let speed = params.lander_init_horizontal_speed_max();
+        if speed > 0.0 {
+            let direction = Vec2::from_angle(rng.random_range(0.0..TAU));
+            self.velocity += (direction * rng.random_range(0.0..=speed)).extend(0.0);
+        }
+        let tilt = params.lander_init_tilt_max();
+        if tilt > 0.0 {
+            let axis = Vec2::from_angle(rng.random_range(0.0..TAU)).extend(0.0);
+            self.rotation *= Quat::from_axis_angle(axis, rng.random_range(0.0..=tilt));
+        }
+        let angular_speed = params.lander_init_angular_speed_max();
+        if angular_speed > 0.0 {
+            self.angular_velocity = Vec3::from_array(
+                [(); 3].map(|()| rng.random_range(-angular_speed..=angular_speed)),
+            );
+        }
+    }
+
+    /// Starts the lander far uprange of the landing zone, flying towards it at high speed.
+    ///
+    /// The lander approaches from the same direction as it would in the terminal phase, and is
+    /// placed upright above the curved surface of the body, so that it has to brake and descend
+    /// its way into the terminal phase. The rate of descent is set to reach the ground at about the
+    /// time that a constant braking burn would bring the lander to a stop over the landing zone.
+    fn powered_descent(params: &Parameters, landscape: &Landscape) -> Self {
+        let radius = f64::from(params.body().radius);
+        let altitude = f64::from(params.powered_descent_altitude());
+        let range = f64::from(params.powered_descent_range());
+        let speed = params.powered_descent_speed();
+
+        // Work in double precision, since the body's radius swamps the altitude.
+        let uprange = landscape
+            .lander_init_position()
+            .truncate()
+            .try_normalize()
+            .unwrap_or(Vec2::X)
+            .extend(0.0)
+            .as_dvec3();
+        let angle = range / radius;
+        let up = uprange * angle.sin() + DVec3::Z * angle.cos();
+        let downrange = -uprange * angle.cos() + DVec3::Z * angle.sin();
+        let position = up * (radius + altitude) - DVec3::Z * radius;
+
+        let braking_time = 2.0 * range as f32 / speed.max(1.0);
+        let vertical_velocity = -altitude as f32 / braking_time;
+        // The descent can start faster than the pilot could set the target, in which case the
+        // target starts at the fastest setting, and the engine brakes the descent down to it.
+        let vertical_velocity_target = vertical_velocity.clamp(
+            VERTICAL_VELOCITY_TARGET_RANGE.start,
+            VERTICAL_VELOCITY_TARGET_RANGE.end,
+        );
+        Self {
+            position: position.as_vec3(),
+            velocity: downrange.as_vec3() * speed + up.as_vec3() * vertical_velocity,
+            rotation: Quat::from_rotation_arc(Vec3::Z, up.as_vec3()),
+            angular_velocity: Vec3::ZERO,
+            vertical_velocity_target,
+            fuel_mass: params.lander_init_fuel_mass() + params.powered_descent_fuel_mass(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use foxglove::websocket::{Parameter, ParameterValue};
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    /// Returns default parameters stored in `dir`, with the given values set.
+    fn parameters(dir: &std::path::Path, values: &[(&str, ParameterValue)]) -> Parameters {
+        let params = Parameters::load(&dir.join("parameters.json"), dir, dir).unwrap();
+        let values = values
+            .iter()
+            .map(|(name, value)| Parameter {
+                name: name.to_string(),
+                r#type: None,
+                value: Some(value.clone()),
+            })
+            .collect();
+        params.set(values).unwrap();
+        params
+    }
+
+    #[test]
+    fn test_powered_descent_target_is_in_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let params = parameters(
+            dir.path(),
+            &[("powered_descent", ParameterValue::Bool(true))],
+        );
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let landscape = Landscape::new(&mut rng, &params, None);
+        let state = InitialState::new(&mut rng, &params, &landscape);
+        let up = params.body().up(state.position);
+        // The lunar descent starts faster than the fastest target the pilot can set.
+        assert!(state.velocity.dot(up) < VERTICAL_VELOCITY_TARGET_RANGE.start);
+        assert_eq!(
+            state.vertical_velocity_target,
+            VERTICAL_VELOCITY_TARGET_RANGE.start
+        );
+    }
+
+    #[test]
+    fn test_same_seed_same_initial_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let params = parameters(
+            dir.path(),
+            &[
+                ("init_horizontal_speed_max", ParameterValue::Number(5.0)),
+                ("init_tilt_max", ParameterValue::Number(10.0)),
+                ("init_angular_speed_max", ParameterValue::Number(0.1)),
+            ],
+        );
+        let initial_state = |seed| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            let landscape = Landscape::new(&mut rng, &params, None);
+            format!("{:?}", InitialState::new(&mut rng, &params, &landscape))
+        };
+        assert_eq!(initial_state(1), initial_state(1));
+        assert_ne!(initial_state(1), initial_state(2));
+    }
+}
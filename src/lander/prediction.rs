@@ -0,0 +1,174 @@
+use foxglove::LazyChannel;
+use foxglove::schemas::{
+    Color, LinePrimitive, Point3, Pose, SceneEntity, SceneEntityDeletion, SceneUpdate,
+    SpherePrimitive, Vector3, line_primitive, scene_entity_deletion,
+};
+use glam::{Quat, Vec2, Vec3};
+
+use crate::convert::IntoFg;
+use crate::landscape::Landscape;
+
+static LANDER_PREDICTION: LazyChannel<SceneUpdate> = LazyChannel::new("/lander_prediction");
+
+/// Number of line segments used to draw the touchdown ellipse.
+const ELLIPSE_SEGMENTS: u32 = 32;
+
+/// Minimum radius of the touchdown ellipse, in meters.
+const ELLIPSE_MIN_RADIUS: f32 = 1.0;
+
+/// Growth of the touchdown ellipse along the direction of travel, as a fraction of the
+/// predicted horizontal distance travelled.
+const ELLIPSE_ALONG_TRACK_GROWTH: f32 = 0.1;
+
+/// Growth of the touchdown ellipse across the direction of travel, in meters per second until
+/// touchdown.
+const ELLIPSE_CROSS_TRACK_GROWTH: f32 = 0.05;
+
+/// A predicted trajectory for the lander.
+pub struct Prediction {
+    trajectory: Vec<Vec3>,
+    touchdown: Option<Touchdown>,
+}
+
+/// A predicted touchdown.
+struct Touchdown {
+    position: Vec3,
+    time_to_go: f32,
+    horizontal_velocity: Vec2,
+}
+
+impl Prediction {
+    pub(super) fn new(start: Vec3) -> Self {
+        Self {
+            trajectory: vec![start],
+            touchdown: None,
+        }
+    }
+
+    pub(super) fn push(&mut self, position: Vec3) {
+        self.trajectory.push(position);
+    }
+
+    pub(super) fn set_touchdown(&mut self, position: Vec3, velocity: Vec3, time_to_go: f32) {
+        self.trajectory.push(position);
+        self.touchdown = Some(Touchdown {
+            position,
+            time_to_go,
+            horizontal_velocity: velocity.truncate(),
+        });
+    }
+
+    #[cfg(test)]
+    pub(super) fn touchdown_position(&self) -> Option<Vec3> {
+        self.touchdown.as_ref().map(|touchdown| touchdown.position)
+    }
+
+    fn trajectory_entity(&self) -> SceneEntity {
+        SceneEntity {
+            id: "trajectory".into(),
+            frame_id: "landing_zone".into(),
+            lines: vec![LinePrimitive {
+                r#type: line_primitive::Type::LineStrip.into(),
+                thickness: 2.0,
+                scale_invariant: true,
+                points: self.trajectory.iter().map(|p| p.into_fg()).collect(),
+                color: Some(Color {
+                    r: 0.0,
+                    g: 0.8,
+                    b: 1.0,
+                    a: 0.8,
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn touchdown_entity(touchdown: &Touchdown, landscape: &Landscape) -> SceneEntity {
+        let ground = landscape.ground_height(touchdown.position.truncate());
+        let center = touchdown.position.truncate().extend(ground + 0.1);
+
+        // The ellipse is a rough dispersion estimate, stretched along the direction of travel.
+        let speed = touchdown.horizontal_velocity.length();
+        let along = ELLIPSE_MIN_RADIUS + ELLIPSE_ALONG_TRACK_GROWTH * speed * touchdown.time_to_go;
+        let across = ELLIPSE_MIN_RADIUS + ELLIPSE_CROSS_TRACK_GROWTH * touchdown.time_to_go;
+        let heading = if speed > f32::EPSILON {
+            touchdown.horizontal_velocity.to_angle()
+        } else {
+            0.0
+        };
+        let color = Color {
+            r: 0.0,
+            g: 0.8,
+            b: 1.0,
+            a: 0.8,
+        };
+        SceneEntity {
+            id: "touchdown".into(),
+            frame_id: "landing_zone".into(),
+            spheres: vec![SpherePrimitive {
+                pose: Some(Pose {
+                    position: Some(center.into_fg()),
+                    ..Default::default()
+                }),
+                size: Some(Vector3 {
+                    x: 1.0,
+                    y: 1.0,
+                    z: 1.0,
+                }),
+                color: Some(color),
+            }],
+            lines: vec![LinePrimitive {
+                r#type: line_primitive::Type::LineLoop.into(),
+                pose: Some(Pose {
+                    position: Some(center.into_fg()),
+                    orientation: Some(Quat::from_rotation_z(heading).into_fg()),
+                }),
+                thickness: 2.0,
+                scale_invariant: true,
+                points: (0..ELLIPSE_SEGMENTS)
+                    .map(|i| {
+                        let angle = i as f32 * std::f32::consts::TAU / ELLIPSE_SEGMENTS as f32;
+                        Point3 {
+                            x: (along * angle.cos()).into(),
+                            y: (across * angle.sin()).into(),
+                            z: 0.0,
+                        }
+                    })
+                    .collect(),
+                color: Some(color),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Logs the predicted trajectory and touchdown point.
+    pub fn log_scene(&self, landscape: &Landscape) {
+        let mut update = SceneUpdate {
+            entities: vec![self.trajectory_entity()],
+            ..Default::default()
+        };
+        match &self.touchdown {
+            Some(touchdown) => update
+                .entities
+                .push(Self::touchdown_entity(touchdown, landscape)),
+            None => update.deletions.push(SceneEntityDeletion {
+                id: "touchdown".into(),
+                ..Default::default()
+            }),
+        }
+        LANDER_PREDICTION.log(&update);
+    }
+
+    /// Clears the predicted trajectory and touchdown point.
+    pub fn clear_scene() {
+        LANDER_PREDICTION.log(&SceneUpdate {
+            deletions: vec![SceneEntityDeletion {
+                r#type: scene_entity_deletion::Type::All.into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+    }
+}
@@ -0,0 +1,101 @@
+use foxglove::LazyChannel;
+use foxglove::schemas::{
+    Color, CylinderPrimitive, LinePrimitive, Pose, SceneEntity, SceneEntityDeletion, SceneUpdate,
+    TextPrimitive, Vector3, line_primitive,
+};
+use glam::{Quat, Vec3};
+
+use crate::convert::IntoFg;
+use crate::landscape::Landscape;
+
+static LANDER_SHADOW: LazyChannel<SceneUpdate> = LazyChannel::new("/lander_shadow");
+static LANDER_DROP_LINE: LazyChannel<SceneUpdate> = LazyChannel::new("/lander_drop_line");
+
+/// Logs the lander's shadow, projected onto the terrain along the sun direction.
+pub(super) fn log_shadow(position: Vec3, footprint_diameter: f32, landscape: &Landscape) {
+    let sun = landscape.sun_direction();
+    let Some(hit) = landscape.raycast(position, -sun) else {
+        LANDER_SHADOW.log(&SceneUpdate {
+            deletions: vec![SceneEntityDeletion {
+                id: "shadow".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        return;
+    };
+
+    // The shadow is stretched away from the sun as it sinks towards the horizon.
+    let azimuth = sun.y.atan2(sun.x);
+    let stretch = 1.0 / sun.z.max(0.1);
+    let diameter = f64::from(footprint_diameter);
+    LANDER_SHADOW.log(&SceneUpdate {
+        entities: vec![SceneEntity {
+            id: "shadow".into(),
+            frame_id: "landing_zone".into(),
+            cylinders: vec![CylinderPrimitive {
+                pose: Some(Pose {
+                    position: Some((hit + Vec3::Z * 0.05).into_fg()),
+                    orientation: Some(Quat::from_rotation_z(azimuth).into_fg()),
+                }),
+                size: Some(Vector3 {
+                    x: diameter * f64::from(stretch),
+                    y: diameter,
+                    z: 0.05,
+                }),
+                bottom_scale: 1.0,
+                top_scale: 1.0,
+                color: Some(Color {
+                    r: 0.0,
+                    g: 0.0,
+                    b: 0.0,
+                    a: 0.5,
+                }),
+            }],
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+}
+
+/// Logs a vertical line from the lander to the terrain directly below it, annotated with the
+/// radar altitude.
+pub(super) fn log_drop_line(position: Vec3, landscape: &Landscape) {
+    let ground = position
+        .truncate()
+        .extend(landscape.ground_height(position.truncate()));
+    let altitude = position.z - ground.z;
+    let color = Color {
+        r: 1.0,
+        g: 1.0,
+        b: 1.0,
+        a: 0.8,
+    };
+    LANDER_DROP_LINE.log(&SceneUpdate {
+        entities: vec![SceneEntity {
+            id: "drop_line".into(),
+            frame_id: "landing_zone".into(),
+            lines: vec![LinePrimitive {
+                r#type: line_primitive::Type::LineList.into(),
+                thickness: 1.0,
+                scale_invariant: true,
+                points: vec![position.into_fg(), ground.into_fg()],
+                color: Some(color),
+                ..Default::default()
+            }],
+            texts: vec![TextPrimitive {
+                pose: Some(Pose {
+                    position: Some(position.lerp(ground, 0.5).into_fg()),
+                    ..Default::default()
+                }),
+                billboard: true,
+                font_size: 14.0,
+                scale_invariant: true,
+                color: Some(color),
+                text: format!("{altitude:.1} m"),
+            }],
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+}
@@ -0,0 +1,145 @@
+use foxglove::LazyChannel;
+use foxglove::schemas::{
+    Color, CylinderPrimitive, Pose, SceneEntity, SceneEntityDeletion, SceneUpdate, Vector3,
+};
+use glam::{Quat, Vec3};
+
+use super::vehicle::StageShape;
+use crate::body::Body;
+use crate::convert::IntoFg;
+
+static DESCENT_STAGE: LazyChannel<SceneUpdate> = LazyChannel::new("/descent_stage");
+
+/// A descent stage that has been staged off during an abort.
+///
+/// Once separated, the descent stage is unpowered, and falls ballistically towards the body's
+/// center until it hits the surface.
+#[derive(Debug, Clone)]
+pub(super) struct DescentStage {
+    position: Vec3,
+    velocity: Vec3,
+    rotation: Quat,
+    shape: StageShape,
+    body: &'static Body,
+    /// Gravitational acceleration at the surface, in meters/s^2.
+    gravity: f32,
+    /// Whether the descent stage has come to rest on the surface.
+    resting: bool,
+}
+
+impl DescentStage {
+    /// Separates the descent stage from a lander with the given state.
+    pub(super) fn separate(
+        position: Vec3,
+        velocity: Vec3,
+        rotation: Quat,
+        shape: StageShape,
+        body: &'static Body,
+        gravity: f32,
+    ) -> Self {
+        Self {
+            position,
+            velocity,
+            rotation,
+            shape,
+            body,
+            gravity,
+            resting: false,
+        }
+    }
+
+    /// Advances the descent stage by `dt`.
+    pub(super) fn step(&mut self, dt: f32) {
+        if self.resting {
+            return;
+        }
+        let up = self.body.up(self.position);
+        let scale = self.body.radius / (self.body.radius + self.body.altitude(self.position));
+        self.velocity -= self.gravity * scale * scale * up * dt;
+        self.position += self.velocity * dt;
+        let altitude = self.body.altitude(self.position);
+        if altitude <= 0.0 {
+            // Come to rest on the surface, directly below where it hit.
+            self.position -= self.body.up(self.position) * altitude;
+            self.velocity = Vec3::ZERO;
+            self.resting = true;
+        }
+    }
+
+    /// Logs the descent stage.
+    pub(super) fn log_scene(&self) {
+        let diameter = f64::from(self.shape.diameter);
+        let center = self.position + self.rotation * (Vec3::Z * self.shape.center_z);
+        DESCENT_STAGE.log(&SceneUpdate {
+            entities: vec![SceneEntity {
+                id: "descent_stage".into(),
+                frame_id: "landing_zone".into(),
+                cylinders: vec![CylinderPrimitive {
+                    pose: Some(Pose {
+                        position: Some(center.into_fg()),
+                        orientation: Some(self.rotation.into_fg()),
+                    }),
+                    size: Some(Vector3 {
+                        x: diameter,
+                        y: diameter,
+                        z: self.shape.height.into(),
+                    }),
+                    bottom_scale: 1.0,
+                    top_scale: 1.0,
+                    color: Some(Color {
+                        r: 0.8,
+                        g: 0.65,
+                        b: 0.2,
+                        a: 1.0,
+                    }),
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+    }
+
+    /// Clears the descent stage from the scene.
+    pub(super) fn clear_scene() {
+        DESCENT_STAGE.log(&SceneUpdate {
+            deletions: vec![SceneEntityDeletion {
+                id: "descent_stage".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::body::BODIES;
+    use crate::lander::VEHICLES;
+
+    #[test]
+    fn test_descent_stage_comes_to_rest_on_surface() {
+        for body in &BODIES {
+            // Separate far enough downrange that the surface has curved away below the horizon.
+            let mut stage = DescentStage::separate(
+                Vec3::new(2000.0, 0.0, 100.0),
+                Vec3::new(5.0, 0.0, 2.0),
+                Quat::IDENTITY,
+                VEHICLES[0].descent_stage,
+                body,
+                body.gravity,
+            );
+            for _ in 0..30_000 {
+                stage.step(1.0 / 30.0);
+            }
+            assert!(body.altitude(stage.position).abs() < 0.01, "{}", body.name);
+            assert!(stage.position.z < 0.0);
+            assert_eq!(stage.velocity, Vec3::ZERO);
+            // It drifted along while it fell, and stays put once it's down.
+            assert!(stage.position.x > 2000.0);
+            let rest = stage.position;
+            stage.step(1.0 / 30.0);
+            assert_eq!(stage.position, rest);
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use foxglove::schemas::{Color, CylinderPrimitive, ModelPrimitive, Pose, SceneEntity, Vector3};
+use glam::Vec3;
+
+use crate::convert::IntoFg;
+
+/// Names of the vehicles in the catalog, indexed by the value of the vehicle parameter.
+pub const VEHICLE_NAMES: [&str; 3] = ["apollo_lm", "hopper", "starship"];
+
+/// How a vehicle is drawn in the scene.
+#[derive(Debug)]
+pub enum Model {
+    /// A model asset, served by the websocket server.
+    Asset(&'static str),
+    /// A cylinder standing on the lander origin, tapering towards the top.
+    Cylinder {
+        diameter: f32,
+        height: f32,
+        top_scale: f32,
+        color: (f64, f64, f64),
+    },
+}
+
+/// Shape of the descent stage, which is staged off during an abort.
+#[derive(Debug, Clone, Copy)]
+pub struct StageShape {
+    pub diameter: f32,
+    pub height: f32,
+    /// Height of the stage's center above the lander origin.
+    pub center_z: f32,
+}
+
+/// A vehicle definition.
+///
+/// Masses, thrust and RCS performance are the defaults for the corresponding parameters, which
+/// are updated when the vehicle is selected. The rest is fixed for each vehicle.
+#[derive(Debug)]
+pub struct Vehicle {
+    pub name: &'static str,
+    /// Dry mass of the descent stage, in kg.
+    pub dry_mass: f32,
+    /// Dry mass of the ascent stage, in kg.
+    pub ascent_stage_dry_mass: f32,
+    /// Ascent stage fuel mass, in kg.
+    pub ascent_stage_fuel_mass: f32,
+    /// Descent engine thrust, in newtons.
+    pub descent_engine_thrust: f32,
+    /// Descent engine fuel burn rate at full thrust, in kg/s.
+    pub descent_fuel_burn_rate: f32,
+    /// Ascent engine thrust, in newtons.
+    pub ascent_engine_thrust: f32,
+    /// Ascent engine fuel burn rate, in kg/s.
+    pub ascent_fuel_burn_rate: f32,
+    /// RCS thrust for strafing, in newtons.
+    pub rcs_thrust: f32,
+    /// RCS torque, in newton-meters.
+    pub rcs_torque: f32,
+    /// Moments of inertia per kilogram of mass, in m^2.
+    pub inertia: Vec3,
+    /// Distance of the RCS quads from the vehicle's axis.
+    pub rcs_quad_distance: f32,
+    /// Height of the RCS quads above the lander origin.
+    pub rcs_quad_z: f32,
+    /// Length of the surface-sensing probes extending below the landing pads.
+    ///
+    /// The contact light illuminates when the probes touch the surface.
+    pub contact_probe_length: f32,
+    /// Diameter of the vehicle's footprint, including the landing legs.
+    pub footprint_diameter: f32,
+    pub descent_stage: StageShape,
+    pub model: Model,
+}
+
+/// Moments of inertia per kilogram of mass, for a solid cylinder.
+const fn cylinder_inertia(diameter: f32, height: f32) -> Vec3 {
+    let r = diameter / 2.0;
+    let xy = (3.0 * r * r + height * height) / 12.0;
+    Vec3::new(xy, xy, r * r / 2.0)
+}
+
+/// The vehicle catalog, in the same order as `VEHICLE_NAMES`.
+pub static VEHICLES: [Vehicle; 3] = [
+    // The Apollo lunar module.
+    //
+    // The module had sixteen 440N thrusters arranged in quads. For any direction, there are two
+    // thrusters to use for strafing, and we're estimating that they're about 2m from the center
+    // of mass for torque.
+    //
+    // The lander is roughly round in the horizontal plane with a diameter of 4.2m, and it's 7m
+    // tall with both ascent & descent stages, so we model its inertia as a cylinder.
+    Vehicle {
+        name: VEHICLE_NAMES[0],
+        dry_mass: 2_150.0,
+        ascent_stage_dry_mass: 2_150.0,
+        ascent_stage_fuel_mass: 2_400.0,
+        descent_engine_thrust: 45_000.0,
+        descent_fuel_burn_rate: 15.0,
+        // Unlike the descent engine, the ascent engine could not be throttled.
+        ascent_engine_thrust: 15_600.0,
+        ascent_fuel_burn_rate: 5.1,
+        rcs_thrust: 880.0,
+        rcs_torque: 3700.0,
+        inertia: cylinder_inertia(4.2, 7.0),
+        rcs_quad_distance: 2.0,
+        rcs_quad_z: 3.5,
+        contact_probe_length: 1.7,
+        footprint_diameter: 9.0,
+        descent_stage: StageShape {
+            diameter: 4.2,
+            height: 1.7,
+            center_z: 1.5,
+        },
+        model: Model::Asset("package://foxglove-lunar-lander/assets/apollo.dae"),
+    },
+    // A small, nimble hopper, with a light ascent stage for getting out of trouble.
+    Vehicle {
+        name: VEHICLE_NAMES[1],
+        dry_mass: 600.0,
+        ascent_stage_dry_mass: 500.0,
+        ascent_stage_fuel_mass: 300.0,
+        descent_engine_thrust: 8_000.0,
+        descent_fuel_burn_rate: 4.0,
+        ascent_engine_thrust: 3_000.0,
+        ascent_fuel_burn_rate: 1.5,
+        rcs_thrust: 250.0,
+        rcs_torque: 500.0,
+        inertia: cylinder_inertia(2.0, 2.5),
+        rcs_quad_distance: 1.0,
+        rcs_quad_z: 1.5,
+        contact_probe_length: 0.5,
+        footprint_diameter: 4.0,
+        descent_stage: StageShape {
+            diameter: 2.0,
+            height: 1.0,
+            center_z: 0.8,
+        },
+        model: Model::Cylinder {
+            diameter: 2.0,
+            height: 2.5,
+            top_scale: 0.8,
+            color: (0.75, 0.75, 0.8),
+        },
+    },
+    // A tall, heavy vertical lander, in the style of Starship.
+    //
+    // It's slow to turn, and needs a lot of thrust to hover.
+    Vehicle {
+        name: VEHICLE_NAMES[2],
+        dry_mass: 15_000.0,
+        ascent_stage_dry_mass: 5_000.0,
+        ascent_stage_fuel_mass: 3_000.0,
+        descent_engine_thrust: 120_000.0,
+        descent_fuel_burn_rate: 12.0,
+        ascent_engine_thrust: 40_000.0,
+        ascent_fuel_burn_rate: 10.0,
+        rcs_thrust: 3_000.0,
+        rcs_torque: 20_000.0,
+        inertia: cylinder_inertia(4.0, 14.0),
+        rcs_quad_distance: 2.0,
+        rcs_quad_z: 12.0,
+        contact_probe_length: 1.0,
+        footprint_diameter: 6.0,
+        descent_stage: StageShape {
+            diameter: 4.0,
+            height: 8.0,
+            center_z: 4.0,
+        },
+        model: Model::Cylinder {
+            diameter: 4.0,
+            height: 14.0,
+            top_scale: 0.5,
+            color: (0.8, 0.8, 0.85),
+        },
+    },
+];
+
+impl Vehicle {
+    /// Area of the vehicle's cross-section, in m^2.
+    pub fn frontal_area(&self) -> f32 {
+        let r = self.descent_stage.diameter / 2.0;
+        std::f32::consts::PI * r * r
+    }
+
+    /// Returns a scene entity for the vehicle, in the lander frame.
+    pub fn scene_entity(&self) -> SceneEntity {
+        let mut entity = SceneEntity {
+            id: "lander".into(),
+            frame_id: "lander".into(),
+            ..Default::default()
+        };
+        match self.model {
+            Model::Asset(url) => entity.models.push(ModelPrimitive {
+                url: url.into(),
+                pose: Some(Pose::default()),
+                scale: Some(Vector3 {
+                    x: 1.0,
+                    y: 1.0,
+                    z: 1.0,
+                }),
+                ..Default::default()
+            }),
+            Model::Cylinder {
+                diameter,
+                height,
+                top_scale,
+                color: (r, g, b),
+            } => entity.cylinders.push(CylinderPrimitive {
+                pose: Some(Pose {
+                    position: Some((Vec3::Z * height / 2.0).into_fg()),
+                    ..Default::default()
+                }),
+                size: Some(Vector3 {
+                    x: diameter.into(),
+                    y: diameter.into(),
+                    z: height.into(),
+                }),
+                bottom_scale: 1.0,
+                top_scale: top_scale.into(),
+                color: Some(Color { r, g, b, a: 1.0 }),
+            }),
+        }
+        entity
+    }
+}
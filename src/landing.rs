@@ -1,8 +1,11 @@
+use std::fmt;
 use std::{collections::HashMap, sync::LazyLock};
 
 use foxglove::LazyChannel;
 use rand::seq::IndexedRandom;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::parameters::Parameters;
 
 static LANDING_REPORT: LazyChannel<LandingReportMsg> = LazyChannel::new("/landing_report");
 
@@ -48,12 +51,108 @@ static REMARKS: LazyLock<HashMap<LandingCriterionType, Vec<&'static str>>> = Laz
     .collect()
 });
 
-#[derive(Debug, Clone, Copy, Serialize, schemars::JsonSchema)]
-#[serde(rename = "snake_case")]
+static ABORT_REMARKS: &[&str] = &[
+    "Discretion is the better part of valor.",
+    "Houston, we've had a change of plans.",
+];
+
+/// Weights for scoring a landing.
+///
+/// A landing's score is the sum of these components:
+///
+/// - Criteria: each landing criterion scores by the fraction of its limit left unused, capped to
+///   `[-1, 1]` so that a single wild miss can't dominate, and scaled by `criterion_weight`.
+/// - Precision: the distance from target criterion is scored in the same way, but scaled by
+///   `criterion_weight * precision_weight`.
+/// - Fuel: a successful landing earns `fuel_weight` times the fraction of descent fuel remaining.
+/// - Time: `time_penalty` points are deducted for each second of flight.
+///
+/// An abort scores no criteria or precision points, and earns half of the fuel points, by the
+/// fraction of ascent fuel remaining.
+///
+/// So that every crash ranks below every other outcome, the time penalty never takes a game that
+/// didn't crash below zero, and a crash loses all of the points that the other components could
+/// have earned, plus `CRASH_PENALTY`.
+#[derive(Debug, Clone, Copy)]
+pub struct ScoringModel {
+    criterion_weight: f32,
+    precision_weight: f32,
+    fuel_weight: f32,
+    time_penalty: f32,
+}
+
+/// Points lost by a crash, on top of everything the other components could have earned, so that
+/// a crash scores below zero even when the other weights are zero.
+const CRASH_PENALTY: f32 = 1.0;
+
+impl ScoringModel {
+    pub fn new(params: &Parameters) -> Self {
+        Self {
+            criterion_weight: params.score_criterion_weight(),
+            precision_weight: params.score_precision_weight(),
+            fuel_weight: params.score_fuel_weight(),
+            time_penalty: params.score_time_penalty(),
+        }
+    }
+
+    fn time(&self, flight_time: f32) -> f32 {
+        -self.time_penalty * flight_time
+    }
+
+    fn fuel(&self, fuel_fraction: f32) -> f32 {
+        self.fuel_weight * fuel_fraction.clamp(0.0, 1.0)
+    }
+
+    fn criterion(&self, criterion: &LandingCriterion) -> f32 {
+        let weight = match criterion.r#type {
+            LandingCriterionType::DistanceFromTarget => {
+                self.criterion_weight * self.precision_weight
+            }
+            _ => self.criterion_weight,
+        };
+        weight * criterion.score().clamp(-1.0, 1.0)
+    }
+
+    /// Returns the penalty for a crash, given the criteria that were judged.
+    fn crash(&self, criteria: &[LandingCriterion]) -> f32 {
+        let max_criteria: f32 = criteria
+            .iter()
+            .map(|c| self.criterion(&LandingCriterion { actual: 0.0, ..*c }))
+            .sum();
+        -(max_criteria + self.fuel(1.0) + CRASH_PENALTY)
+    }
+}
+
+/// A breakdown of a landing's score into its components.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, schemars::JsonSchema)]
+pub struct ScoreBreakdown {
+    criteria: f32,
+    precision: f32,
+    fuel: f32,
+    time: f32,
+    crash: f32,
+}
+
+impl ScoreBreakdown {
+    fn total(&self) -> f32 {
+        self.criteria + self.precision + self.fuel + self.time + self.crash
+    }
+
+    /// Limits the time penalty so that the total isn't below zero.
+    fn floor_time(mut self) -> Self {
+        let others = self.total() - self.time;
+        self.time = self.time.max(-others.max(0.0));
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum LandingStatus {
     Landed,
     Missed,
     Crashed,
+    Aborted,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, schemars::JsonSchema)]
@@ -67,6 +166,16 @@ pub enum LandingCriterionType {
 }
 
 impl LandingCriterionType {
+    fn label(self) -> &'static str {
+        match self {
+            Self::VerticalSpeed => "vertical speed",
+            Self::HorizontalSpeed => "horizontal speed",
+            Self::Tilt => "tilt",
+            Self::AngularSpeed => "angular speed",
+            Self::DistanceFromTarget => "distance from target",
+        }
+    }
+
     fn choose_remark(self) -> &'static str {
         REMARKS
             .get(&self)
@@ -122,7 +231,7 @@ impl LandingCriterion {
         }
     }
 
-    fn ok(&self) -> bool {
+    pub fn ok(&self) -> bool {
         self.actual <= self.max
     }
 
@@ -131,11 +240,71 @@ impl LandingCriterion {
     }
 }
 
+/// Limits for the landing criteria.
+#[derive(Debug, Clone, Copy)]
+pub struct LandingLimits {
+    max_vertical_speed: f32,
+    max_horizontal_speed: f32,
+    max_tilt: f32,
+    max_angular_speed: f32,
+    max_distance: f32,
+}
+
+impl LandingLimits {
+    pub fn new(params: &Parameters) -> Self {
+        Self {
+            max_vertical_speed: params.landing_max_vertical_speed(),
+            max_horizontal_speed: params.landing_max_horizontal_speed(),
+            max_tilt: params.landing_max_tilt(),
+            max_angular_speed: params.landing_max_angular_speed(),
+            max_distance: params.landing_zone_radius() as f32,
+        }
+    }
+
+    /// Vertical speed criterion, in m/s.
+    pub fn vertical_speed(&self, actual: f32) -> LandingCriterion {
+        LandingCriterion::vertical_speed(self.max_vertical_speed, actual)
+    }
+
+    /// Horizontal speed criterion, in m/s.
+    pub fn horizontal_speed(&self, actual: f32) -> LandingCriterion {
+        LandingCriterion::horizontal_speed(self.max_horizontal_speed, actual)
+    }
+
+    /// Tilt from upright criterion, in radians.
+    pub fn tilt(&self, actual: f32) -> LandingCriterion {
+        LandingCriterion::tilt(self.max_tilt, actual)
+    }
+
+    /// Angular speed criterion, in radians/s.
+    pub fn angular_speed(&self, actual: f32) -> LandingCriterion {
+        LandingCriterion::angular_speed(self.max_angular_speed, actual)
+    }
+
+    /// Distance from the center of the landing zone criterion, in meters.
+    pub fn distance_from_target(&self, actual: f32) -> LandingCriterion {
+        LandingCriterion::distance_from_target(self.max_distance, actual)
+    }
+}
+
+impl fmt::Display for LandingCriterion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:.2} (max {:.2})",
+            self.r#type.label(),
+            self.actual,
+            self.max
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
 pub struct LandingReport {
     status: LandingStatus,
     remark: &'static str,
     score: f32,
+    breakdown: ScoreBreakdown,
     criteria: Vec<LandingCriterion>,
 }
 
@@ -146,20 +315,45 @@ pub struct LandingReportMsg<'a> {
 }
 
 impl LandingReport {
-    pub fn new(criteria: Vec<LandingCriterion>) -> Self {
-        let mut score = 0.0;
+    /// Creates a report for a landing, with the fraction of descent fuel remaining and the
+    /// flight time in seconds.
+    pub fn new(
+        scoring: &ScoringModel,
+        criteria: Vec<LandingCriterion>,
+        fuel_fraction: f32,
+        flight_time: f32,
+    ) -> Self {
+        let mut breakdown = ScoreBreakdown {
+            time: scoring.time(flight_time),
+            ..Default::default()
+        };
         let mut first_problem = None;
         for crit in &criteria {
             if !crit.ok() && first_problem.is_none() {
                 first_problem = Some(crit.r#type);
             }
-            score += crit.score() * 2.0;
+            match crit.r#type {
+                LandingCriterionType::DistanceFromTarget => {
+                    breakdown.precision += scoring.criterion(crit)
+                }
+                _ => breakdown.criteria += scoring.criterion(crit),
+            }
+        }
+        if first_problem.is_none() {
+            breakdown.fuel = scoring.fuel(fuel_fraction);
         }
         let status = match first_problem {
             None => LandingStatus::Landed,
             Some(LandingCriterionType::DistanceFromTarget) => LandingStatus::Missed,
             Some(_) => LandingStatus::Crashed,
         };
+        let breakdown = match status {
+            LandingStatus::Crashed => ScoreBreakdown {
+                crash: scoring.crash(&criteria),
+                ..breakdown
+            },
+            _ => breakdown.floor_time(),
+        };
         let remark = match first_problem {
             Some(p) => p.choose_remark(),
             None => "The eagle has landed.",
@@ -167,16 +361,52 @@ impl LandingReport {
         Self {
             status,
             remark,
-            score,
+            score: breakdown.total(),
+            breakdown,
             criteria,
         }
     }
 
+    /// Creates a report for an aborted landing, with the fraction of ascent fuel remaining when
+    /// the ascent stage makes it back up, and the flight time in seconds.
+    pub fn aborted(scoring: &ScoringModel, ascent_fuel_fraction: f32, flight_time: f32) -> Self {
+        let breakdown = ScoreBreakdown {
+            fuel: 0.5 * scoring.fuel(ascent_fuel_fraction),
+            time: scoring.time(flight_time),
+            ..Default::default()
+        }
+        .floor_time();
+        Self {
+            status: LandingStatus::Aborted,
+            remark: ABORT_REMARKS.choose(&mut rand::rng()).unwrap(),
+            score: breakdown.total(),
+            breakdown,
+            criteria: vec![],
+        }
+    }
+
     /// Returns the landing status.
     pub fn status(&self) -> LandingStatus {
         self.status
     }
 
+    /// Returns the landing score.
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+
+    /// Returns the remark on the landing.
+    pub fn remark(&self) -> &'static str {
+        self.remark
+    }
+
+    /// Returns the criterion with the lowest score, if any.
+    pub fn worst_criterion(&self) -> Option<&LandingCriterion> {
+        self.criteria
+            .iter()
+            .min_by(|a, b| a.score().total_cmp(&b.score()))
+    }
+
     /// Clears the previous landing report.
     pub fn clear() {
         LANDING_REPORT.log(&LandingReportMsg::default());
@@ -187,3 +417,150 @@ impl LandingReport {
         LANDING_REPORT.log(&LandingReportMsg { report: Some(self) })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCORING: ScoringModel = ScoringModel {
+        criterion_weight: 2.0,
+        precision_weight: 2.0,
+        fuel_weight: 5.0,
+        time_penalty: 0.02,
+    };
+
+    fn criteria(vertical_speed: f32, distance: f32) -> Vec<LandingCriterion> {
+        vec![
+            LandingCriterion::vertical_speed(3.0, vertical_speed),
+            LandingCriterion::horizontal_speed(1.0, 0.0),
+            LandingCriterion::tilt(3.0, 0.0),
+            LandingCriterion::angular_speed(0.25, 0.0),
+            LandingCriterion::distance_from_target(20.0, distance),
+        ]
+    }
+
+    fn assert_breakdown(report: &LandingReport, expected: ScoreBreakdown) {
+        let actual = report.breakdown;
+        for (name, a, e) in [
+            ("criteria", actual.criteria, expected.criteria),
+            ("precision", actual.precision, expected.precision),
+            ("fuel", actual.fuel, expected.fuel),
+            ("time", actual.time, expected.time),
+            ("crash", actual.crash, expected.crash),
+        ] {
+            assert!((a - e).abs() < 1e-4, "{name}: {a} != {e}");
+        }
+        assert!((report.score - expected.total()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_perfect_landing() {
+        let report = LandingReport::new(&SCORING, criteria(0.0, 0.0), 1.0, 0.0);
+        assert!(matches!(report.status(), LandingStatus::Landed));
+        assert_breakdown(
+            &report,
+            ScoreBreakdown {
+                criteria: 8.0,
+                precision: 4.0,
+                fuel: 5.0,
+                time: 0.0,
+                crash: 0.0,
+            },
+        );
+    }
+
+    #[test]
+    fn test_typical_landing() {
+        let report = LandingReport::new(&SCORING, criteria(1.5, 5.0), 0.4, 60.0);
+        assert!(matches!(report.status(), LandingStatus::Landed));
+        assert_breakdown(
+            &report,
+            ScoreBreakdown {
+                criteria: 7.0,
+                precision: 3.0,
+                fuel: 2.0,
+                time: -1.2,
+                crash: 0.0,
+            },
+        );
+    }
+
+    #[test]
+    fn test_crash_is_penalized_and_earns_no_fuel() {
+        let report = LandingReport::new(&SCORING, criteria(30.0, 0.0), 0.8, 50.0);
+        assert!(matches!(report.status(), LandingStatus::Crashed));
+        assert_breakdown(
+            &report,
+            ScoreBreakdown {
+                criteria: 4.0,
+                precision: 4.0,
+                fuel: 0.0,
+                time: -1.0,
+                crash: -18.0,
+            },
+        );
+    }
+
+    #[test]
+    fn test_crashes_rank_below_everything_else() {
+        // The best possible crash: just over one limit, straight away.
+        let crash = LandingReport::new(&SCORING, criteria(3.01, 0.0), 1.0, 0.0);
+        assert!(matches!(crash.status(), LandingStatus::Crashed));
+        // The worst landings: at every limit, or off target, after a long flight, out of fuel.
+        let worst = [
+            LandingReport::new(&SCORING, criteria(3.0, 20.0), 0.0, 1000.0),
+            LandingReport::new(&SCORING, criteria(3.0, 100.0), 0.0, 1000.0),
+            LandingReport::aborted(&SCORING, 0.0, 1000.0),
+        ];
+        for report in worst {
+            assert!(!matches!(report.status(), LandingStatus::Crashed));
+            assert!(
+                crash.score() < report.score(),
+                "{:?} {} <= crash {}",
+                report.status(),
+                report.score(),
+                crash.score()
+            );
+        }
+    }
+
+    #[test]
+    fn test_missed_landing() {
+        let report = LandingReport::new(&SCORING, criteria(0.0, 100.0), 0.5, 50.0);
+        assert!(matches!(report.status(), LandingStatus::Missed));
+        assert_breakdown(
+            &report,
+            ScoreBreakdown {
+                criteria: 8.0,
+                precision: -4.0,
+                fuel: 0.0,
+                time: -1.0,
+                crash: 0.0,
+            },
+        );
+    }
+
+    #[test]
+    fn test_abort() {
+        let report = LandingReport::aborted(&SCORING, 0.5, 50.0);
+        assert!(matches!(report.status(), LandingStatus::Aborted));
+        assert_breakdown(
+            &report,
+            ScoreBreakdown {
+                criteria: 0.0,
+                precision: 0.0,
+                fuel: 1.25,
+                time: -1.0,
+                crash: 0.0,
+            },
+        );
+    }
+
+    #[test]
+    fn test_status_is_snake_case() {
+        let json = serde_json::to_string(&LandingStatus::Crashed).unwrap();
+        assert_eq!(json, r#""crashed""#);
+        let status: LandingStatus = serde_json::from_str(r#""landed""#).unwrap();
+        assert!(matches!(status, LandingStatus::Landed));
+    }
+}
@@ -1,33 +1,57 @@
 use foxglove::LazyChannel;
 use foxglove::schemas::{FrameTransform, SceneUpdate, Vector3};
-use glam::Vec3;
+use glam::{Vec2, Vec3};
 use rand::prelude::*;
 
 mod height_map;
 mod landing_zone;
 use height_map::HeightMap;
+pub use height_map::LANDING_ZONE_BLEND_RADIUS;
 use landing_zone::LandingZone;
 
+use crate::body::Body;
 use crate::parameters::Parameters;
 
 static LANDSCAPE: LazyChannel<SceneUpdate> = LazyChannel::new("/landscape");
 
+/// Distance between samples when casting a ray against the terrain, in meters.
+const RAYCAST_STEP: f32 = 0.5;
+
+/// Maximum number of samples when casting a ray against the terrain.
+const RAYCAST_MAX_STEPS: usize = 8000;
+
 pub struct Landscape {
     frame_transform: FrameTransform,
     scene_update: SceneUpdate,
+    height_map: HeightMap,
+    landing_zone_center: Vec3,
     landing_zone: LandingZone,
     lander_init_position: Vec3,
+    sun_direction: Vec3,
+    body: &'static Body,
 }
 impl Landscape {
-    pub fn new<R: Rng>(rng: &mut R, params: &Parameters) -> Self {
+    /// Generates a landscape, with a landing zone at `landing_zone` from its center, or at random
+    /// if `None`.
+    pub fn new<R: Rng>(rng: &mut R, params: &Parameters, landing_zone: Option<Vec2>) -> Self {
         LANDSCAPE.init();
-        let mut height_map = HeightMap::new(rng, params.landscape_width());
-        let landing_zone_center = height_map.create_random_landing_zone(
+        let body = params.body();
+        let mut height_map = HeightMap::new(
             rng,
-            params.landing_zone_min_distance(),
-            params.landing_zone_max_distance(),
-            params.landing_zone_radius(),
+            params.landscape_width(),
+            params.terrain_roughness(),
+            &body.terrain,
         );
+        let landing_zone_center = match landing_zone {
+            Some(offset) => height_map.create_landing_zone_at(offset, params.landing_zone_radius()),
+            None => height_map.create_random_landing_zone(
+                rng,
+                params.landing_zone_min_distance(),
+                params.landing_zone_max_distance(),
+                params.landing_zone_radius(),
+            ),
+        };
+        height_map.curve(landing_zone_center, |distance| body.surface_drop(distance));
         let lander_init_position =
             height_map.center() - landing_zone_center + (Vec3::Z * params.lander_init_altitude());
         let frame_transform = FrameTransform {
@@ -47,8 +71,17 @@ impl Landscape {
         Self {
             frame_transform,
             scene_update,
-            landing_zone: landing_zone_center.into(),
+            height_map,
+            landing_zone_center,
+            landing_zone: LandingZone::new(
+                landing_zone_center,
+                params.landing_zone_radius() as f32,
+                params.lander_init_altitude(),
+                params.approach_glide_slope(),
+            ),
             lander_init_position,
+            sun_direction: params.sun_direction(),
+            body,
         }
     }
 
@@ -56,6 +89,52 @@ impl Landscape {
         self.lander_init_position
     }
 
+    /// Returns the height of the terrain at the given point, relative to the landing zone.
+    ///
+    /// Points that fall outside of the landscape are assumed to be on a smooth surface, curving
+    /// away from the landing zone with the body.
+    pub fn ground_height(&self, point: Vec2) -> f32 {
+        let map = point + self.landing_zone_center.truncate();
+        self.height_map.height_at(map.x, map.y).map_or_else(
+            || -self.body.surface_drop(point.length()),
+            |z| z - self.landing_zone_center.z,
+        )
+    }
+
+    /// Returns the height of `point` above the terrain directly below it, which is negative if
+    /// the point is underground.
+    pub fn height_above_ground(&self, point: Vec3) -> f32 {
+        point.z - self.ground_height(point.truncate())
+    }
+
+    /// Casts a ray from `origin` along `direction`, and returns the first point where it meets
+    /// the terrain, relative to the landing zone.
+    pub fn raycast(&self, origin: Vec3, direction: Vec3) -> Option<Vec3> {
+        let direction = direction.normalize();
+        let above = |p: Vec3| self.height_above_ground(p);
+        let mut prev = origin;
+        if above(prev) <= 0.0 {
+            return Some(prev);
+        }
+        for step in 1..=RAYCAST_MAX_STEPS {
+            let next = origin + direction * (step as f32 * RAYCAST_STEP);
+            let h = above(next);
+            if h <= 0.0 {
+                // Interpolate between the last two samples to find the crossing.
+                let h_prev = above(prev);
+                let t = h_prev / (h_prev - h);
+                return Some(prev.lerp(next, t));
+            }
+            prev = next;
+        }
+        None
+    }
+
+    /// Returns a unit vector pointing towards the sun.
+    pub fn sun_direction(&self) -> Vec3 {
+        self.sun_direction
+    }
+
     pub fn frame_transforms(&self) -> Vec<FrameTransform> {
         vec![
             self.frame_transform.clone(),
@@ -65,6 +144,11 @@ impl Landscape {
 
     pub fn log_scene(&self) {
         LANDSCAPE.log(&self.scene_update);
-        self.landing_zone.log_scene();
+    }
+
+    /// Logs the landing zone markers, colored by whether the lander is within the approach
+    /// criteria.
+    pub fn log_landing_zone(&self, pass: bool) {
+        self.landing_zone.log_scene(pass);
     }
 }
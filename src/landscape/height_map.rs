@@ -3,8 +3,10 @@ use glam::{Vec2, Vec3};
 use noise::{NoiseFn, Perlin};
 use rand::prelude::*;
 
-const DEFAULT_NOISE_SCALE: f64 = 0.1;
-const DEFAULT_Z_SCALE: f64 = 2.0;
+use crate::body::TerrainStyle;
+
+/// Width of the ring around a landing zone where it's blended into the surrounding terrain.
+pub const LANDING_ZONE_BLEND_RADIUS: u32 = 3;
 
 /// A square map of z values.
 pub struct HeightMap {
@@ -12,16 +14,19 @@ pub struct HeightMap {
     z: Vec<f64>,
 }
 impl HeightMap {
-    pub fn new<R: Rng>(rng: &mut R, width: u32) -> HeightMap {
-        let z_scale = DEFAULT_Z_SCALE;
-        let noise_scale = DEFAULT_NOISE_SCALE;
+    pub fn new<R: Rng>(rng: &mut R, width: u32, roughness: f32, style: &TerrainStyle) -> HeightMap {
+        let z_scale = f64::from(roughness) * style.relief;
         let perlin = Perlin::new(rng.random());
-        let z = (0..width)
-            .flat_map(|x| {
-                (0..width).map(move |y| {
-                    z_scale * perlin.get([x as f64 * noise_scale, y as f64 * noise_scale])
+        let height = move |x: f64, y: f64| {
+            (0..style.octaves)
+                .map(|octave| {
+                    let scale = style.noise_scale * f64::from(1 << octave);
+                    perlin.get([x * scale, y * scale]) / f64::from(1 << octave)
                 })
-            })
+                .sum::<f64>()
+        };
+        let z = (0..width)
+            .flat_map(|x| (0..width).map(move |y| z_scale * height(x as f64, y as f64)))
             .collect();
         Self { width, z }
     }
@@ -58,8 +63,14 @@ impl HeightMap {
         self.create_landing_zone(vec.x as u32, vec.y as u32, radius)
     }
 
+    /// Creates a landing zone at `offset` from the center of the map.
+    pub fn create_landing_zone_at(&mut self, offset: Vec2, radius: u32) -> Vec3 {
+        let vec = offset + self.center2();
+        self.create_landing_zone(vec.x as u32, vec.y as u32, radius)
+    }
+
     fn create_landing_zone(&mut self, center_x: u32, center_y: u32, radius: u32) -> Vec3 {
-        let blend_radius = radius + 3;
+        let blend_radius = radius + LANDING_ZONE_BLEND_RADIUS;
         let center_z = self.get(center_x, center_y);
 
         for ix in (center_x - blend_radius)..=(center_x + blend_radius) {
@@ -85,6 +96,33 @@ impl HeightMap {
         }
     }
 
+    /// Lowers the terrain by `drop` at each horizontal distance from `center`, to follow the
+    /// curvature of the body.
+    pub fn curve(&mut self, center: Vec3, drop: impl Fn(f32) -> f32) {
+        for ix in 0..self.width {
+            for iy in 0..self.width {
+                let distance = Vec2::new(ix as f32, iy as f32).distance(center.truncate());
+                let z = self.get(ix, iy) - f64::from(drop(distance));
+                self.set(ix, iy, z);
+            }
+        }
+    }
+
+    /// Returns the terrain height at the given (fractional) map coordinates, using bilinear
+    /// interpolation. Returns `None` if the coordinates fall outside of the map.
+    pub fn height_at(&self, x: f32, y: f32) -> Option<f32> {
+        let max = (self.width - 1) as f32;
+        if !(0.0..=max).contains(&x) || !(0.0..=max).contains(&y) {
+            return None;
+        }
+        let (ix, iy) = (x.floor() as u32, y.floor() as u32);
+        let (ix1, iy1) = ((ix + 1).min(self.width - 1), (iy + 1).min(self.width - 1));
+        let (tx, ty) = (f64::from(x.fract()), f64::from(y.fract()));
+        let z0 = (1.0 - tx) * self.get(ix, iy) + tx * self.get(ix1, iy);
+        let z1 = (1.0 - tx) * self.get(ix, iy1) + tx * self.get(ix1, iy1);
+        Some(((1.0 - ty) * z0 + ty * z1) as f32)
+    }
+
     fn set(&mut self, ix: u32, iy: u32, z: f64) {
         let idx = (ix * self.width + iy) as usize;
         self.z[idx] = z;
@@ -1,6 +1,7 @@
 use foxglove::LazyChannel;
 use foxglove::schemas::{
-    Color, FrameTransform, SceneEntity, SceneUpdate, SpherePrimitive, Vector3,
+    Color, CylinderPrimitive, FrameTransform, LinePrimitive, Point3, Pose, SceneEntity,
+    SceneUpdate, SpherePrimitive, Vector3, line_primitive,
 };
 use glam::Vec3;
 
@@ -8,17 +9,28 @@ use crate::convert::IntoFg;
 
 static LANDING_ZONE: LazyChannel<SceneUpdate> = LazyChannel::new("/landing_zone");
 
+/// Number of line segments used to draw the landing zone ring.
+const RING_SEGMENTS: u32 = 64;
+
+/// Color of the landing zone markers when the lander is within the approach criteria.
+const COLOR_PASS: (f64, f64, f64) = (0.0, 1.0, 0.0);
+
+/// Color of the landing zone markers when the lander is outside the approach criteria.
+const COLOR_FAIL: (f64, f64, f64) = (1.0, 0.0, 0.0);
+
 pub struct LandingZone {
     frame_transform: FrameTransform,
-    scene_update: SceneUpdate,
-}
-impl From<Vec3> for LandingZone {
-    fn from(value: Vec3) -> Self {
-        LandingZone::new(value)
-    }
+    radius: f32,
+    corridor_height: f32,
+    glide_slope: f32,
 }
 impl LandingZone {
-    pub fn new(center: Vec3) -> Self {
+    /// Creates a new landing zone.
+    ///
+    /// The `radius` is the pass/fail distance from the center of the landing zone. The approach
+    /// corridor and glide-slope cone extend upwards to `corridor_height`, and the cone's surface
+    /// rises at `glide_slope` degrees above the horizon.
+    pub fn new(center: Vec3, radius: f32, corridor_height: f32, glide_slope: f32) -> Self {
         LANDING_ZONE.init();
         let frame_transform = FrameTransform {
             parent_frame_id: "landscape".into(),
@@ -26,31 +38,11 @@ impl LandingZone {
             translation: Some(center.into_fg()),
             ..Default::default()
         };
-        let scene_update = SceneUpdate {
-            entities: vec![SceneEntity {
-                id: "landing_zone".into(),
-                frame_id: "landing_zone".into(),
-                spheres: vec![SpherePrimitive {
-                    size: Some(Vector3 {
-                        x: 2.0,
-                        y: 2.0,
-                        z: 1.0,
-                    }),
-                    color: Some(Color {
-                        r: 1.0,
-                        g: 1.0,
-                        b: 0.0,
-                        a: 0.7,
-                    }),
-                    ..Default::default()
-                }],
-                ..Default::default()
-            }],
-            ..Default::default()
-        };
         Self {
             frame_transform,
-            scene_update,
+            radius,
+            corridor_height,
+            glide_slope,
         }
     }
 
@@ -58,7 +50,109 @@ impl LandingZone {
         self.frame_transform.clone()
     }
 
-    pub fn log_scene(&self) {
-        LANDING_ZONE.log(&self.scene_update);
+    /// Radius of the glide-slope cone at the top of the approach corridor.
+    fn cone_top_radius(&self) -> f32 {
+        self.radius + self.corridor_height / self.glide_slope.to_radians().tan()
+    }
+
+    fn ring(&self, color: Color) -> LinePrimitive {
+        let radius = f64::from(self.radius);
+        LinePrimitive {
+            r#type: line_primitive::Type::LineLoop.into(),
+            thickness: 3.0,
+            scale_invariant: true,
+            points: (0..RING_SEGMENTS)
+                .map(|i| {
+                    let angle = f64::from(i) * std::f64::consts::TAU / f64::from(RING_SEGMENTS);
+                    Point3 {
+                        x: radius * angle.cos(),
+                        y: radius * angle.sin(),
+                        z: 0.05,
+                    }
+                })
+                .collect(),
+            color: Some(color),
+            ..Default::default()
+        }
+    }
+
+    fn corridor(&self, color: Color) -> CylinderPrimitive {
+        let diameter = f64::from(self.radius) * 2.0;
+        let height = f64::from(self.corridor_height);
+        CylinderPrimitive {
+            pose: Some(Pose {
+                position: Some(Vector3 {
+                    z: height / 2.0,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            size: Some(Vector3 {
+                x: diameter,
+                y: diameter,
+                z: height,
+            }),
+            bottom_scale: 1.0,
+            top_scale: 1.0,
+            color: Some(Color { a: 0.08, ..color }),
+        }
+    }
+
+    fn cone(&self, color: Color) -> CylinderPrimitive {
+        let top_radius = self.cone_top_radius();
+        let diameter = f64::from(top_radius) * 2.0;
+        let height = f64::from(self.corridor_height);
+        CylinderPrimitive {
+            pose: Some(Pose {
+                position: Some(Vector3 {
+                    z: height / 2.0,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            size: Some(Vector3 {
+                x: diameter,
+                y: diameter,
+                z: height,
+            }),
+            bottom_scale: f64::from(self.radius / top_radius),
+            top_scale: 1.0,
+            color: Some(Color { a: 0.05, ..color }),
+        }
+    }
+
+    fn scene_entity(&self, pass: bool) -> SceneEntity {
+        let (r, g, b) = if pass { COLOR_PASS } else { COLOR_FAIL };
+        let color = Color { r, g, b, a: 0.7 };
+        SceneEntity {
+            id: "landing_zone".into(),
+            frame_id: "landing_zone".into(),
+            spheres: vec![SpherePrimitive {
+                size: Some(Vector3 {
+                    x: 2.0,
+                    y: 2.0,
+                    z: 1.0,
+                }),
+                color: Some(Color {
+                    r: 1.0,
+                    g: 1.0,
+                    b: 0.0,
+                    a: 0.7,
+                }),
+                ..Default::default()
+            }],
+            lines: vec![self.ring(color)],
+            cylinders: vec![self.corridor(color), self.cone(color)],
+            ..Default::default()
+        }
+    }
+
+    /// Logs the landing zone markers, colored by whether the lander is currently within the
+    /// approach criteria: rate of descent, tilt and angular speed.
+    pub fn log_scene(&self, pass: bool) {
+        LANDING_ZONE.log(&SceneUpdate {
+            entities: vec![self.scene_entity(pass)],
+            ..Default::default()
+        });
     }
 }
@@ -0,0 +1,258 @@
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use foxglove::LazyChannel;
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+
+use crate::landing::LandingStatus;
+
+static LEADERBOARD: LazyChannel<LeaderboardMsg> = LazyChannel::new("/leaderboard");
+
+/// Number of top scores kept for each seed and difficulty.
+const TOP_SCORES: usize = 10;
+
+/// A score on the leaderboard.
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Entry {
+    pub score: f32,
+    pub status: LandingStatus,
+    /// When the game was played, in RFC 3339 format.
+    pub timestamp: String,
+    /// File name of the game's recording.
+    pub recording: String,
+}
+
+/// Top scores for a seed and difficulty.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Board {
+    seed: u64,
+    difficulty: String,
+    scores: Vec<Entry>,
+}
+
+/// Where a newly recorded score placed.
+#[derive(Debug, Clone, Copy, Default, Serialize, schemars::JsonSchema)]
+pub struct Standing {
+    /// Rank among the top scores for the seed and difficulty, starting at 1, if it made the cut.
+    pub rank: Option<usize>,
+    /// Whether the score is a personal best for the difficulty, across all seeds.
+    pub personal_best: bool,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+struct LeaderboardMsg<'a> {
+    seed: u64,
+    difficulty: &'a str,
+    top_scores: &'a [Entry],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    personal_best: Option<&'a Entry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    standing: Option<Standing>,
+}
+
+/// A local leaderboard, persisted as a JSON file.
+pub struct Leaderboard {
+    /// Where the leaderboard is saved, or `None` if it's only kept in memory.
+    path: Option<PathBuf>,
+    boards: Vec<Board>,
+}
+
+impl Leaderboard {
+    /// Loads the leaderboard from `path`, or starts a new one if the file doesn't exist.
+    ///
+    /// A leaderboard that can't be parsed is moved aside, rather than overwritten, and a new one
+    /// is started in its place. If the file can't be read or moved aside, the error is logged, and
+    /// a new leaderboard is kept in memory for the session, leaving the file alone.
+    pub fn load(path: &Path) -> Self {
+        match Self::read(path) {
+            Ok(boards) => Self {
+                path: Some(path.to_path_buf()),
+                boards,
+            },
+            Err(e) => {
+                eprintln!("keeping scores in memory only: {e:#}");
+                Self {
+                    path: None,
+                    boards: vec![],
+                }
+            }
+        }
+    }
+
+    fn read(path: &Path) -> anyhow::Result<Vec<Board>> {
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+        let file = std::fs::File::open(path).context("failed to open leaderboard")?;
+        match serde_json::from_reader(BufReader::new(file)) {
+            Ok(boards) => Ok(boards),
+            Err(e) => {
+                let backup = path.with_extension("json.corrupt");
+                eprintln!("moving corrupt leaderboard to {backup:?}: {e}");
+                std::fs::rename(path, &backup)
+                    .context("failed to move corrupt leaderboard aside")?;
+                Ok(vec![])
+            }
+        }
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let file = NamedTempFile::new_in(dir).context("create leaderboard file")?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, &self.boards).context("write leaderboard")?;
+        writer
+            .into_inner()
+            .context("flush leaderboard")?
+            .persist(path)
+            .context("rename leaderboard file")?;
+        Ok(())
+    }
+
+    fn board(&self, seed: u64, difficulty: &str) -> Option<&Board> {
+        self.boards
+            .iter()
+            .find(|b| b.seed == seed && b.difficulty == difficulty)
+    }
+
+    /// Returns the best score for the difficulty, across all seeds.
+    fn personal_best(&self, difficulty: &str) -> Option<&Entry> {
+        self.boards
+            .iter()
+            .filter(|b| b.difficulty == difficulty)
+            .filter_map(|b| b.scores.first())
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+    }
+
+    /// Records a score, saves the leaderboard, and returns where the score placed.
+    ///
+    /// If the leaderboard can't be saved, the score is still kept in memory for the rest of the
+    /// session.
+    pub fn record(&mut self, seed: u64, difficulty: &str, entry: Entry) -> Standing {
+        let personal_best = self
+            .personal_best(difficulty)
+            .is_none_or(|best| entry.score > best.score);
+        let index = match self
+            .boards
+            .iter()
+            .position(|b| b.seed == seed && b.difficulty == difficulty)
+        {
+            Some(index) => index,
+            None => {
+                self.boards.push(Board {
+                    seed,
+                    difficulty: difficulty.to_string(),
+                    scores: vec![],
+                });
+                self.boards.len() - 1
+            }
+        };
+        let scores = &mut self.boards[index].scores;
+        let rank = scores.partition_point(|e| e.score >= entry.score);
+        scores.insert(rank, entry);
+        scores.truncate(TOP_SCORES);
+        if let Err(e) = self.save() {
+            eprintln!("failed to save leaderboard: {e:#}");
+        }
+        Standing {
+            rank: (rank < TOP_SCORES).then_some(rank + 1),
+            personal_best,
+        }
+    }
+
+    /// Logs the top scores for the seed and difficulty, along with the personal best, and where
+    /// the latest score placed, if any.
+    pub fn log(&self, seed: u64, difficulty: &str, standing: Option<Standing>) {
+        LEADERBOARD.log(&LeaderboardMsg {
+            seed,
+            difficulty,
+            top_scores: self
+                .board(seed, difficulty)
+                .map_or(&[], |b| b.scores.as_slice()),
+            personal_best: self.personal_best(difficulty),
+            standing,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: f32) -> Entry {
+        Entry {
+            score,
+            status: LandingStatus::Landed,
+            timestamp: String::new(),
+            recording: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_rank_and_personal_best() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("leaderboard.json");
+        let mut leaderboard = Leaderboard::load(&path);
+
+        let standing = leaderboard.record(1, "normal", entry(5.0));
+        assert_eq!(standing.rank, Some(1));
+        assert!(standing.personal_best);
+
+        let standing = leaderboard.record(1, "normal", entry(3.0));
+        assert_eq!(standing.rank, Some(2));
+        assert!(!standing.personal_best);
+
+        // A personal best is across all seeds, but not across difficulties.
+        let standing = leaderboard.record(2, "normal", entry(4.0));
+        assert_eq!(standing.rank, Some(1));
+        assert!(!standing.personal_best);
+        let standing = leaderboard.record(2, "hard", entry(1.0));
+        assert!(standing.personal_best);
+
+        // Scores below the top ten don't place.
+        for _ in 0..TOP_SCORES {
+            leaderboard.record(1, "normal", entry(4.0));
+        }
+        let standing = leaderboard.record(1, "normal", entry(2.0));
+        assert_eq!(standing.rank, None);
+
+        // The scores are saved.
+        let leaderboard = Leaderboard::load(&path);
+        let board = leaderboard.board(1, "normal").unwrap();
+        assert_eq!(board.scores.len(), TOP_SCORES);
+        assert_eq!(board.scores[0].score, 5.0);
+    }
+
+    #[test]
+    fn test_corrupt_file_is_moved_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("leaderboard.json");
+        std::fs::write(&path, "not json").unwrap();
+        let mut leaderboard = Leaderboard::load(&path);
+        assert!(leaderboard.boards.is_empty());
+        assert_eq!(
+            std::fs::read_to_string(path.with_extension("json.corrupt")).unwrap(),
+            "not json"
+        );
+        leaderboard.record(1, "normal", entry(5.0));
+        assert_eq!(Leaderboard::load(&path).boards.len(), 1);
+    }
+
+    #[test]
+    fn test_corrupt_file_is_left_alone_if_it_cant_be_moved() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("leaderboard.json");
+        std::fs::write(&path, "not json").unwrap();
+        // A file can't be renamed over a directory.
+        std::fs::create_dir(path.with_extension("json.corrupt")).unwrap();
+        let mut leaderboard = Leaderboard::load(&path);
+        let standing = leaderboard.record(1, "normal", entry(5.0));
+        assert_eq!(standing.rank, Some(1));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "not json");
+    }
+}
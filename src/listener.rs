@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use foxglove::websocket::{Client, ClientChannel, ServerListener};
+use foxglove::websocket::{Client, ClientChannel, ServerListener, Status, StatusLevel};
 
 use crate::controls::{Controls, GamepadMsg};
 use crate::parameters::Parameters;
@@ -51,10 +51,18 @@ impl ServerListener for Listener {
 
     fn on_set_parameters(
         &self,
-        _client: Client,
+        client: Client,
         params: Vec<foxglove::websocket::Parameter>,
         _request_id: Option<&str>,
     ) -> Vec<foxglove::websocket::Parameter> {
-        self.params.set(params)
+        let names: Vec<_> = params.iter().map(|p| p.name.clone()).collect();
+        match self.params.set(params) {
+            Ok(updated) => updated,
+            Err(e) => {
+                // Tell the client why, and send back the current values so it can revert them.
+                client.send_status(Status::new(StatusLevel::Error, format!("{e:#}")));
+                self.params.get(names)
+            }
+        }
     }
 }
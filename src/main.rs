@@ -1,40 +1,55 @@
-use std::io::BufWriter;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Context;
-use banner::Banner;
+use banner::{Banner, Banners};
 use chrono::Utc;
 use controls::Gamepad;
 use foxglove::schemas::FrameTransforms;
 use foxglove::websocket::Capability;
-use foxglove::{LazyChannel, McapWriter, WebSocketServer};
-use landing::LandingReport;
+use foxglove::{LazyChannel, WebSocketServer, WebSocketServerHandle};
+use landing::{LandingLimits, LandingReport, LandingStatus, ScoringModel};
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 
 mod assets;
 mod banner;
+mod body;
 mod controls;
 mod convert;
+mod events;
+mod faults;
 mod lander;
 mod landing;
 mod landscape;
+mod leaderboard;
 mod listener;
 mod parameters;
+mod recording;
+mod scenario;
+mod sensors;
 
+use body::Wind;
 use controls::Controls;
-use lander::Lander;
+use events::Event;
+use faults::Faults;
+use lander::{FuelCallouts, InitialState, Lander, LanderConfig, Prediction};
 use landscape::Landscape;
+use leaderboard::{Entry, Leaderboard};
 use listener::Listener;
-use parameters::Parameters;
-use tempfile::NamedTempFile;
+use parameters::{Parameters, Subscription};
+use recording::Recording;
+use sensors::Sensors;
 
 static FT: LazyChannel<FrameTransforms> = LazyChannel::new("/ft");
 
 const GAME_STEP_DURATION: Duration = Duration::from_millis(33);
 
+/// Number of game steps between updates of the predicted trajectory, which is expensive to
+/// simulate.
+const PREDICTION_PERIOD_STEPS: u32 = 5;
+
 #[tokio::main]
 async fn main() {
     if let Err(e) = fallible_main().await {
@@ -43,15 +58,22 @@ async fn main() {
 }
 
 async fn fallible_main() -> anyhow::Result<()> {
-    let params = Arc::new(Parameters::default());
+    let params = Arc::new(Parameters::load(
+        Path::new("./parameters.json"),
+        Path::new("./profiles"),
+        Path::new("./scenarios"),
+    )?);
+    params.write_schema(Path::new("./parameters.schema.json"))?;
     let gamepad = Gamepad::from_json_file("gamepad.json")?;
     let controls = Arc::new(Controls::new(gamepad));
     let recordings_dir = PathBuf::from("./recordings");
     if !recordings_dir.exists() {
         std::fs::create_dir_all(&recordings_dir).context("failed to create recordings dir")?;
     }
+    let leaderboard = Leaderboard::load(Path::new("./leaderboard.json"));
     let server = WebSocketServer::new()
         .name("fg-lander")
+        // The parameters capability includes subscribing to parameter changes.
         .capabilities([Capability::ClientPublish, Capability::Parameters])
         .supported_encodings(["json"])
         .fetch_asset_handler_blocking_fn(assets::fetch_asset)
@@ -59,99 +81,250 @@ async fn fallible_main() -> anyhow::Result<()> {
         .start()
         .await
         .context("failed to start websocket server")?;
-    tokio::task::spawn(game_loop(recordings_dir, params, controls));
-    tokio::signal::ctrl_c().await.ok();
+    let changes = params.subscribe();
+    tokio::task::spawn(game_loop(recordings_dir, leaderboard, params, controls));
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => (),
+        _ = publish_parameter_changes(&server, changes) => (),
+    }
     server.stop().await;
     Ok(())
 }
 
-async fn game_loop(recordings_dir: PathBuf, params: Arc<Parameters>, controls: Arc<Controls>) {
+/// Publishes parameter changes made by the game to subscribed clients.
+///
+/// The server already publishes changes that are requested by clients.
+async fn publish_parameter_changes(server: &WebSocketServerHandle, mut changes: Subscription) {
+    while let Some(change) = changes.recv().await {
+        if !change.from_client {
+            server.publish_parameter_values(change.parameters);
+        }
+    }
+}
+
+async fn game_loop(
+    recordings_dir: PathBuf,
+    mut leaderboard: Leaderboard,
+    params: Arc<Parameters>,
+    controls: Arc<Controls>,
+) {
+    let mut next_seed = NextSeed::Regenerate;
     loop {
-        if let Err(e) = game_iter(&recordings_dir, &params, &controls).await {
+        next_seed = game_iter(
+            &recordings_dir,
+            &mut leaderboard,
+            &params,
+            &controls,
+            next_seed,
+        )
+        .await
+        .unwrap_or_else(|e| {
             eprintln!("game aborted: {e}");
-        }
+            NextSeed::Regenerate
+        });
     }
 }
 
+/// Whether the next game gets a new seed.
+#[derive(Debug, Clone, Copy)]
+enum NextSeed {
+    /// Regenerate the seed, if the parameters call for it.
+    Regenerate,
+    /// Keep the current seed, because the game is only being set up again.
+    Keep,
+}
+
 async fn game_iter(
     recordings_dir: &Path,
+    leaderboard: &mut Leaderboard,
     params: &Parameters,
     controls: &Controls,
-) -> anyhow::Result<()> {
-    // Initialize game state.
-    let seed = params.next_seed();
+    next_seed: NextSeed,
+) -> anyhow::Result<NextSeed> {
+    // Initialize game state, from the selected scenario if there is one. Pick up any profiles and
+    // scenarios that were added since the last game.
+    params.scan_files();
+    let scenario = params.scenario().unwrap_or_else(|e| {
+        eprintln!("ignoring scenario: {e:#}");
+        None
+    });
+    let difficulty = params.difficulty(scenario.as_ref());
+    let scenario = scenario.unwrap_or_default();
+    let seed = scenario.seed.unwrap_or_else(|| match next_seed {
+        NextSeed::Regenerate => params.next_seed(),
+        NextSeed::Keep => params.seed(),
+    });
     let mut rng = ChaCha8Rng::seed_from_u64(seed);
-    let landscape = Landscape::new(&mut rng, params);
+    let landscape = Landscape::new(&mut rng, params, scenario.landing_zone());
+    let mut initial_state = InitialState::new(&mut rng, params, &landscape);
+    scenario.apply(&mut initial_state);
     let mut lander = Lander::new(
-        landscape.lander_init_position(),
-        params.lander_init_vertical_velocity(),
-        params.lander_init_vertical_velocity_target(),
-        params.landing_zone_radius(),
+        initial_state,
+        LanderConfig::new(params),
+        LandingLimits::new(params),
+        FuelCallouts::new(params.fuel_callouts(), params.fuel_bingo()),
     );
+    let mut sensors = Sensors::new(&mut rng, params, &lander, &landscape);
+    let mut faults = Faults::new(&mut rng, params);
+    let mut wind = Wind::new(&mut rng, params.body());
+    let mut changes = params.subscribe();
+    let mut prediction = lander.predict(&landscape);
 
     // Clear state, log scene once.
     LandingReport::clear();
     controls.soft_reset();
     log_scene_static(&landscape, &lander);
+    leaderboard.log(seed, &difficulty, None);
 
     // Print a banner to tell the user to press start and wait.
-    let banner = Banner::press_start();
+    let dt = GAME_STEP_DURATION.as_secs_f32();
+    let mut banners = Banners::default();
+    banners.push(Banner::press_start());
     while !controls.get_reset_requested() {
-        log_frame_transforms(&landscape, &lander, Some(&banner));
-        banner.log_scene();
+        // Set the game up again if the parameters change, so that the scene reflects them. The
+        // seed is kept, so that only the changed parameters make a difference.
+        if changes.changed() {
+            return Ok(NextSeed::Keep);
+        }
+        banners.update(dt);
+        log_frame_transforms(&landscape, &lander, banners.current());
+        log_scene_dynamic(&landscape, &lander, &prediction);
+        banners.log_scene();
         tokio::time::sleep(GAME_STEP_DURATION).await;
     }
 
-    Banner::clear_scene();
+    // Count down to the start of the game.
+    for banner in Banner::countdown() {
+        banners.push(banner);
+    }
+    while !banners.is_empty() {
+        banners.update(dt);
+        log_frame_transforms(&landscape, &lander, banners.current());
+        banners.log_scene();
+        tokio::time::sleep(GAME_STEP_DURATION).await;
+    }
     controls.soft_reset();
 
     // Start recording an mcap file.
     let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
-    let recording = NamedTempFile::new_in(recordings_dir).unwrap();
-    let mcap_writer = McapWriter::new().create(BufWriter::new(recording)).unwrap();
+    let mut recording = Recording::start(recordings_dir)?;
+    recording.add_metadata("lander_config", lander.config().to_metadata());
+    if !scenario.name.is_empty() {
+        recording.add_metadata("scenario", scenario.to_metadata());
+    }
+    recording.log_event(0.0, &Event::GameStart { seed });
 
     // Log landscape and lander once at the beginning of the game.
     log_scene_static(&landscape, &lander);
 
     // Main game loop.
-    while !lander.has_landed() {
+    let mut mission_time = 0.0;
+    let mut step = 0;
+    while !lander.is_finished() {
         tokio::time::sleep(GAME_STEP_DURATION).await;
-        lander.step(GAME_STEP_DURATION.as_secs_f32(), controls);
-        log_frame_transforms(&landscape, &lander, None);
-        lander.log();
+        mission_time += dt;
+        step += 1;
+        for fault in faults.update(dt) {
+            lander.apply_fault(&fault);
+            sensors.apply_fault(&fault);
+            recording.log_event(mission_time, &Event::Fault(fault));
+        }
+        lander.set_wind(wind.update(dt));
+        sensors.update(dt, &lander, &landscape);
+        for event in lander.step(dt, controls, sensors.estimate()) {
+            if let Some(banner) = Banner::callout(&event) {
+                banners.push(banner);
+            }
+            recording.log_event(mission_time, &event);
+        }
+        if step % PREDICTION_PERIOD_STEPS == 0 {
+            prediction = lander.predict(&landscape);
+        }
+        banners.update(dt);
+        log_frame_transforms(&landscape, &lander, banners.current());
+        log_scene_dynamic(&landscape, &lander, &prediction);
+        banners.log_scene();
+        lander.log(sensors.estimate());
+        lander.log_hud(sensors.estimate());
+        sensors.log();
         if controls.get_reset_requested() {
-            return Ok(());
+            recording.log_event(mission_time, &Event::Reset);
+            recording.finish(&recordings_dir.join(format!("Reset-{timestamp}.mcap")))?;
+            return Ok(NextSeed::Regenerate);
+        }
+        let time_expired = scenario
+            .time_limit
+            .is_some_and(|limit| mission_time >= limit);
+        if time_expired && !lander.is_finished() {
+            recording.log_event(mission_time, &Event::TimeExpired);
+            break;
         }
     }
 
-    // Generate and log a landing report.
-    let report = lander.landing_report().expect("landed");
+    // Without a landing report, the time limit must have run out, so wait for a reset.
+    let scoring = ScoringModel::new(params);
+    let Some(report) = lander.landing_report(&scoring, mission_time) else {
+        banners.clear();
+        banners.push(Banner::time_expired());
+        lander.stop();
+        Prediction::clear_scene();
+        Lander::clear_effects();
+        while !controls.get_reset_requested() {
+            banners.update(dt);
+            log_frame_transforms(&landscape, &lander, banners.current());
+            banners.log_scene();
+            tokio::time::sleep(GAME_STEP_DURATION).await;
+        }
+        recording.log_event(mission_time, &Event::Reset);
+        recording.finish(&recordings_dir.join(format!("TimeExpired-{timestamp}.mcap")))?;
+        return Ok(NextSeed::Regenerate);
+    };
+
+    // Log the landing report.
     let status = report.status();
-    let banner = Banner::landing_status(status);
-    log_frame_transforms(&landscape, &lander, Some(&banner));
-    banner.log_scene();
+    banners.clear();
+    banners.push(Banner::landing_status(status));
+    log_frame_transforms(&landscape, &lander, banners.current());
+    log_scene_dynamic(&landscape, &lander, &prediction);
+    Prediction::clear_scene();
+    Lander::clear_effects();
+    banners.log_scene();
     report.log();
 
-    // Finalize the recording.
-    let recording = mcap_writer
-        .close()
-        .context("flush recording data")?
-        .into_inner()
-        .context("recover named tempfile")?;
-    recording
-        .persist(recordings_dir.join(format!("{status:?}-{timestamp}.mcap")))
-        .context("rename recording file")?;
+    // Log the outcome. The recording is finished once the game is reset, so that it includes the
+    // reset, like games that are reset partway through.
+    let score = report.score();
+    let event = match status {
+        LandingStatus::Aborted => Event::AbortComplete { score },
+        _ => Event::Touchdown { status, score },
+    };
+    recording.log_event(mission_time, &event);
+    let recording_name = format!("{status:?}-{timestamp}.mcap");
+
+    // Add the score to the leaderboard.
+    let entry = Entry {
+        score,
+        status,
+        timestamp: timestamp.to_string(),
+        recording: recording_name.clone(),
+    };
+    let standing = leaderboard.record(seed, &difficulty, entry);
+    banners.push(Banner::landing_summary(&report, standing));
 
     // Halt the lander and log while waiting for a reset.
     lander.stop();
     while !controls.get_reset_requested() {
-        log_frame_transforms(&landscape, &lander, Some(&banner));
-        banner.log_scene();
+        banners.update(dt);
+        log_frame_transforms(&landscape, &lander, banners.current());
+        banners.log_scene();
         report.log();
+        leaderboard.log(seed, &difficulty, Some(standing));
         tokio::time::sleep(GAME_STEP_DURATION).await;
     }
+    recording.log_event(mission_time, &Event::Reset);
+    recording.finish(&recordings_dir.join(&recording_name))?;
 
-    Ok(())
+    Ok(NextSeed::Regenerate)
 }
 
 /// Logs frame transforms.
@@ -170,3 +343,12 @@ fn log_scene_static(landscape: &Landscape, lander: &Lander) {
     landscape.log_scene();
     lander.log_scene();
 }
+
+/// Logs scene entities that depend on the current state of the lander.
+fn log_scene_dynamic(landscape: &Landscape, lander: &Lander, prediction: &Prediction) {
+    landscape.log_landing_zone(lander.approach_criteria_met());
+    prediction.log_scene(landscape);
+    lander.log_effects(landscape);
+    lander.log_altitude_cues(landscape);
+    lander.log_descent_stage();
+}
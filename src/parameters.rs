@@ -1,13 +1,32 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Debug;
 use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
+use anyhow::Context;
 use bytes::Buf;
 use foxglove::websocket::{Parameter, ParameterType, ParameterValue};
+use glam::Vec3;
 use parking_lot::RwLock;
+use serde_json::json;
+use tokio::sync::broadcast;
+
+use crate::body::{BODIES, BODY_NAMES, Body};
+use crate::lander::{VEHICLE_NAMES, VEHICLES, Vehicle};
+use crate::landscape::LANDING_ZONE_BLEND_RADIUS;
+use crate::scenario::Scenario;
+
+mod profile;
 
 static SEED: &str = "seed";
 static REGENERATE_SEED: &str = "regenerate_seed";
+static DIFFICULTY: &str = "difficulty";
+static VEHICLE: &str = "vehicle";
+static BODY: &str = "body";
+static SAVE_PROFILE: &str = "save_profile";
+static LOAD_PROFILE: &str = "load_profile";
+static SCENARIO: &str = "scenario";
 static LANDSCAPE_WIDTH: &str = "landscape_width";
 static LANDING_ZONE_RADIUS: &str = "landing_zone_radius";
 static LANDING_ZONE_MIN_DISTANCE: &str = "landing_zone_min_distance";
@@ -15,6 +34,154 @@ static LANDING_ZONE_MAX_DISTANCE: &str = "landing_zone_max_distance";
 static INIT_ALTITUDE: &str = "init_altitude";
 static INIT_VERTICAL_VELOCITY: &str = "init_vertical_velocity";
 static INIT_VERTICAL_VELOCITY_TARGET: &str = "init_vertical_velocity_target";
+static INIT_FUEL_MASS: &str = "init_fuel_mass";
+static INIT_HORIZONTAL_SPEED_MAX: &str = "init_horizontal_speed_max";
+static INIT_TILT_MAX: &str = "init_tilt_max";
+static INIT_ANGULAR_SPEED_MAX: &str = "init_angular_speed_max";
+static POWERED_DESCENT: &str = "powered_descent";
+static POWERED_DESCENT_ALTITUDE: &str = "powered_descent_altitude";
+static POWERED_DESCENT_RANGE: &str = "powered_descent_range";
+static POWERED_DESCENT_SPEED: &str = "powered_descent_speed";
+static POWERED_DESCENT_FUEL_MASS: &str = "powered_descent_fuel_mass";
+static TERRAIN_ROUGHNESS: &str = "terrain_roughness";
+static LANDER_DRY_MASS: &str = "lander_dry_mass";
+static ASCENT_STAGE_DRY_MASS: &str = "ascent_stage_dry_mass";
+static ASCENT_STAGE_FUEL_MASS: &str = "ascent_stage_fuel_mass";
+static DESCENT_ENGINE_THRUST: &str = "descent_engine_thrust";
+static DESCENT_FUEL_BURN_RATE: &str = "descent_fuel_burn_rate";
+static ASCENT_ENGINE_THRUST: &str = "ascent_engine_thrust";
+static ASCENT_FUEL_BURN_RATE: &str = "ascent_fuel_burn_rate";
+static RCS_THRUST: &str = "rcs_thrust";
+static RCS_TORQUE: &str = "rcs_torque";
+static GRAVITY: &str = "gravity";
+static ANGULAR_DAMPING: &str = "angular_damping";
+static LANDING_MAX_VERTICAL_SPEED: &str = "landing_max_vertical_speed";
+static LANDING_MAX_HORIZONTAL_SPEED: &str = "landing_max_horizontal_speed";
+static LANDING_MAX_TILT: &str = "landing_max_tilt";
+static LANDING_MAX_ANGULAR_SPEED: &str = "landing_max_angular_speed";
+static APPROACH_GLIDE_SLOPE: &str = "approach_glide_slope";
+static SUN_AZIMUTH: &str = "sun_azimuth";
+static SUN_ELEVATION: &str = "sun_elevation";
+static RADAR_ENABLED: &str = "radar_enabled";
+static RADAR_RANGE_NOISE: &str = "radar_range_noise";
+static RADAR_RANGE_BIAS: &str = "radar_range_bias";
+static RADAR_VELOCITY_NOISE: &str = "radar_velocity_noise";
+static RADAR_LOCK_ON_ALTITUDE: &str = "radar_lock_on_altitude";
+static RADAR_DROPOUT_RATE: &str = "radar_dropout_rate";
+static NAV_FILTER_ENABLED: &str = "nav_filter_enabled";
+static IMU_ACCEL_NOISE: &str = "imu_accel_noise";
+static IMU_ACCEL_BIAS: &str = "imu_accel_bias";
+static IMU_GYRO_NOISE: &str = "imu_gyro_noise";
+static IMU_GYRO_BIAS: &str = "imu_gyro_bias";
+static FAULT_RANDOM_RATE: &str = "fault_random_rate";
+static FAULT_ENGINE_OUT_TIME: &str = "fault_engine_out_time";
+static FAULT_STUCK_THRUSTER_TIME: &str = "fault_stuck_thruster_time";
+static FAULT_RADAR_FREEZE_TIME: &str = "fault_radar_freeze_time";
+static FAULT_FUEL_LEAK_TIME: &str = "fault_fuel_leak_time";
+static FAULT_FUEL_LEAK_RATE: &str = "fault_fuel_leak_rate";
+static FUEL_CALLOUTS: &str = "fuel_callouts";
+static FUEL_BINGO: &str = "fuel_bingo";
+static SCORE_CRITERION_WEIGHT: &str = "score_criterion_weight";
+static SCORE_PRECISION_WEIGHT: &str = "score_precision_weight";
+static SCORE_FUEL_WEIGHT: &str = "score_fuel_weight";
+static SCORE_TIME_PENALTY: &str = "score_time_penalty";
+
+/// Largest integer that a float64 represents exactly, which bounds the seed.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Valid range of the initial target vertical velocity, in meters/s.
+pub const VERTICAL_VELOCITY_TARGET_RANGE: Range<f32> = -20.0..0.0;
+
+/// Name of the first variant of the profile and scenario parameters, which selects neither.
+const NONE: &str = "(none)";
+
+/// Names of the difficulty presets, indexed by the value of the difficulty parameter.
+const DIFFICULTY_PRESETS: [&str; 3] = ["trainee", "pilot", "commander"];
+
+/// Parameter values for each difficulty preset, in the same order as `DIFFICULTY_PRESETS`.
+///
+/// The "pilot" preset matches the parameter defaults.
+fn difficulty_preset(index: usize) -> [(&'static str, Data); 9] {
+    let [
+        vertical_speed,
+        horizontal_speed,
+        tilt,
+        angular_speed,
+        fuel,
+        roughness,
+        radius,
+        min_distance,
+        max_distance,
+    ] = match index {
+        0 => [5.0, 2.0, 15.0, 0.5, 1000.0, 1.0, 30.0, 10.0, 30.0],
+        1 => [3.0, 1.0, 10.0, 0.25, 600.0, 2.0, 20.0, 30.0, 70.0],
+        _ => [2.0, 0.5, 5.0, 0.15, 400.0, 4.0, 10.0, 60.0, 85.0],
+    };
+    [
+        (LANDING_MAX_VERTICAL_SPEED, Data::F32(vertical_speed)),
+        (LANDING_MAX_HORIZONTAL_SPEED, Data::F32(horizontal_speed)),
+        (LANDING_MAX_TILT, Data::F32(tilt)),
+        (LANDING_MAX_ANGULAR_SPEED, Data::F32(angular_speed)),
+        (INIT_FUEL_MASS, Data::F32(fuel)),
+        (TERRAIN_ROUGHNESS, Data::F32(roughness)),
+        (LANDING_ZONE_RADIUS, Data::U32(radius as u32)),
+        (LANDING_ZONE_MIN_DISTANCE, Data::F32(min_distance)),
+        (LANDING_ZONE_MAX_DISTANCE, Data::F32(max_distance)),
+    ]
+}
+
+/// Parameter values for each vehicle in the catalog, indexed by the value of the vehicle
+/// parameter.
+///
+/// The Apollo LM matches the parameter defaults.
+fn vehicle_preset(index: usize) -> [(&'static str, Data); 9] {
+    let vehicle = &VEHICLES[index];
+    [
+        (LANDER_DRY_MASS, Data::F32(vehicle.dry_mass)),
+        (
+            ASCENT_STAGE_DRY_MASS,
+            Data::F32(vehicle.ascent_stage_dry_mass),
+        ),
+        (
+            ASCENT_STAGE_FUEL_MASS,
+            Data::F32(vehicle.ascent_stage_fuel_mass),
+        ),
+        (
+            DESCENT_ENGINE_THRUST,
+            Data::F32(vehicle.descent_engine_thrust),
+        ),
+        (
+            DESCENT_FUEL_BURN_RATE,
+            Data::F32(vehicle.descent_fuel_burn_rate),
+        ),
+        (
+            ASCENT_ENGINE_THRUST,
+            Data::F32(vehicle.ascent_engine_thrust),
+        ),
+        (
+            ASCENT_FUEL_BURN_RATE,
+            Data::F32(vehicle.ascent_fuel_burn_rate),
+        ),
+        (RCS_THRUST, Data::F32(vehicle.rcs_thrust)),
+        (RCS_TORQUE, Data::F32(vehicle.rcs_torque)),
+    ]
+}
+
+/// Parameter values for each body in the catalog, indexed by the value of the body parameter.
+///
+/// The Moon matches the parameter defaults.
+fn body_preset(index: usize) -> [(&'static str, Data); 4] {
+    let body = &BODIES[index];
+    [
+        (GRAVITY, Data::F32(body.gravity)),
+        (
+            POWERED_DESCENT_ALTITUDE,
+            Data::F32(body.powered_descent.altitude),
+        ),
+        (POWERED_DESCENT_RANGE, Data::F32(body.powered_descent.range)),
+        (POWERED_DESCENT_SPEED, Data::F32(body.powered_descent.speed)),
+    ]
+}
 
 fn default_values() -> HashMap<String, Value> {
     let params = [
@@ -25,59 +192,389 @@ fn default_values() -> HashMap<String, Value> {
             Data::Bool(true),
             None,
         ),
+        (
+            SAVE_PROFILE,
+            "Save all parameters to the named profile",
+            Data::Text(String::new()),
+            None,
+        ),
+        (
+            LOAD_PROFILE,
+            "Load all parameters from a profile: 0 = none, then the profiles in alphabetical order",
+            Data::Enum(0, variants(&[NONE])),
+            None,
+        ),
+        (
+            SCENARIO,
+            "Play a scenario: 0 = none, then the scenarios in alphabetical order",
+            Data::Enum(0, variants(&[NONE])),
+            None,
+        ),
+        (
+            DIFFICULTY,
+            "Difficulty preset: 0 = trainee, 1 = pilot, 2 = commander",
+            Data::Enum(1, variants(&DIFFICULTY_PRESETS)),
+            None,
+        ),
+        (
+            VEHICLE,
+            "Vehicle: 0 = Apollo LM, 1 = hopper, 2 = Starship-like lander",
+            Data::Enum(0, variants(&VEHICLE_NAMES)),
+            None,
+        ),
+        (
+            BODY,
+            "Body: 0 = Moon, 1 = Mars, 2 = asteroid",
+            Data::Enum(0, variants(&BODY_NAMES)),
+            None,
+        ),
         (
             LANDSCAPE_WIDTH,
             "Width of the landscape, which is always square",
-            Data::F32(200.0),
-            Some(ClampRange(100.0..1000.0).boxed()),
+            Data::U32(200),
+            Some(ValidRange(100..1000).boxed()),
         ),
         (
             LANDING_ZONE_MIN_DISTANCE,
             "Minimum distance between landscape center and landing zone",
             Data::F32(30.0),
-            Some(
-                ClampFn(|reg, data| {
-                    let max = reg.get_f32(LANDSCAPE_WIDTH).unwrap() / 2.0;
-                    ClampRange(0.0..max).clamp(reg, data)
-                })
-                .boxed(),
-            ),
+            Some(ValidRange(0.0..500.0).boxed()),
         ),
         (
             LANDING_ZONE_MAX_DISTANCE,
             "Maximum distance between landscape center and landing zone",
             Data::F32(70.0),
-            Some(
-                ClampFn(|reg, data| {
-                    let max = reg.get_f32(LANDSCAPE_WIDTH).unwrap() / 2.0;
-                    ClampRange(0.0..max).clamp(reg, data)
-                })
-                .boxed(),
-            ),
+            Some(ValidRange(0.0..500.0).boxed()),
         ),
         (
             LANDING_ZONE_RADIUS,
             "Landing zone radius",
-            Data::F32(20.0),
-            Some(ClampRange(5.0..50.0).boxed()),
+            Data::U32(20),
+            Some(ValidRange(5..50).boxed()),
         ),
         (
             INIT_ALTITUDE,
             "Initial lander altitude",
             Data::F32(200.0),
-            Some(ClampRange(100.0..1000.0).boxed()),
+            Some(ValidRange(100.0..1000.0).boxed()),
         ),
         (
             INIT_VERTICAL_VELOCITY,
             "Initial lander vertical velocity",
             Data::F32(0.0),
-            Some(ClampRange(-20.0..0.0).boxed()),
+            Some(ValidRange(-20.0..0.0).boxed()),
         ),
         (
             INIT_VERTICAL_VELOCITY_TARGET,
             "Initial lander vertical velocity_target",
             Data::F32(-6.0),
-            Some(ClampRange(-20.0..0.0).boxed()),
+            Some(ValidRange(VERTICAL_VELOCITY_TARGET_RANGE).boxed()),
+        ),
+        (
+            APPROACH_GLIDE_SLOPE,
+            "Approach cone glide slope, in degrees above the horizon",
+            Data::F32(60.0),
+            Some(ValidRange(30.0..85.0).boxed()),
+        ),
+        (
+            INIT_FUEL_MASS,
+            "Initial descent fuel mass, in kg",
+            Data::F32(600.0),
+            Some(ValidRange(100.0..2000.0).boxed()),
+        ),
+        (
+            INIT_HORIZONTAL_SPEED_MAX,
+            "Maximum random initial horizontal speed, in meters/s",
+            Data::F32(0.0),
+            Some(ValidRange(0.0..20.0).boxed()),
+        ),
+        (
+            INIT_TILT_MAX,
+            "Maximum random initial tilt from upright, in degrees",
+            Data::F32(0.0),
+            Some(ValidRange(0.0..45.0).boxed()),
+        ),
+        (
+            INIT_ANGULAR_SPEED_MAX,
+            "Maximum random initial angular speed about each axis, in radians/s",
+            Data::F32(0.0),
+            Some(ValidRange(0.0..1.0).boxed()),
+        ),
+        (
+            POWERED_DESCENT,
+            "Start with a powered descent from high speed, far uprange of the landing zone",
+            Data::Bool(false),
+            None,
+        ),
+        (
+            POWERED_DESCENT_ALTITUDE,
+            "Altitude at the start of the powered descent, in meters",
+            Data::F32(BODIES[0].powered_descent.altitude),
+            Some(ValidRange(1000.0..50000.0).boxed()),
+        ),
+        (
+            POWERED_DESCENT_RANGE,
+            "Distance to the landing zone along the surface at the start of the powered descent, in meters",
+            Data::F32(BODIES[0].powered_descent.range),
+            Some(ValidRange(1000.0..500000.0).boxed()),
+        ),
+        (
+            POWERED_DESCENT_SPEED,
+            "Horizontal speed at the start of the powered descent, in meters/s",
+            Data::F32(BODIES[0].powered_descent.speed),
+            Some(ValidRange(0.0..2000.0).boxed()),
+        ),
+        (
+            POWERED_DESCENT_FUEL_MASS,
+            "Extra descent fuel for the braking burn during the powered descent, in kg",
+            Data::F32(800.0),
+            Some(ValidRange(0.0..10000.0).boxed()),
+        ),
+        (
+            TERRAIN_ROUGHNESS,
+            "Height scale of the terrain, in meters",
+            Data::F32(2.0),
+            Some(ValidRange(0.0..10.0).boxed()),
+        ),
+        (
+            LANDER_DRY_MASS,
+            "Dry mass of the descent stage, in kg",
+            Data::F32(VEHICLES[0].dry_mass),
+            Some(ValidRange(500.0..20000.0).boxed()),
+        ),
+        (
+            ASCENT_STAGE_DRY_MASS,
+            "Dry mass of the ascent stage, in kg",
+            Data::F32(VEHICLES[0].ascent_stage_dry_mass),
+            Some(ValidRange(500.0..20000.0).boxed()),
+        ),
+        (
+            ASCENT_STAGE_FUEL_MASS,
+            "Ascent stage fuel mass, in kg",
+            Data::F32(VEHICLES[0].ascent_stage_fuel_mass),
+            Some(ValidRange(0.0..10000.0).boxed()),
+        ),
+        (
+            DESCENT_ENGINE_THRUST,
+            "Descent engine thrust, in newtons",
+            Data::F32(VEHICLES[0].descent_engine_thrust),
+            Some(ValidRange(1000.0..200000.0).boxed()),
+        ),
+        (
+            DESCENT_FUEL_BURN_RATE,
+            "Descent engine fuel burn rate at full thrust, in kg/s",
+            Data::F32(VEHICLES[0].descent_fuel_burn_rate),
+            Some(ValidRange(0.1..100.0).boxed()),
+        ),
+        (
+            ASCENT_ENGINE_THRUST,
+            "Ascent engine thrust, in newtons",
+            Data::F32(VEHICLES[0].ascent_engine_thrust),
+            Some(ValidRange(1000.0..100000.0).boxed()),
+        ),
+        (
+            ASCENT_FUEL_BURN_RATE,
+            "Ascent engine fuel burn rate, in kg/s",
+            Data::F32(VEHICLES[0].ascent_fuel_burn_rate),
+            Some(ValidRange(0.1..50.0).boxed()),
+        ),
+        (
+            RCS_THRUST,
+            "RCS thrust for strafing, in newtons",
+            Data::F32(VEHICLES[0].rcs_thrust),
+            Some(ValidRange(0.0..10000.0).boxed()),
+        ),
+        (
+            RCS_TORQUE,
+            "RCS torque, in newton-meters",
+            Data::F32(VEHICLES[0].rcs_torque),
+            Some(ValidRange(0.0..50000.0).boxed()),
+        ),
+        (
+            GRAVITY,
+            "Downward gravitational acceleration, in meters/s^2",
+            Data::F32(BODIES[0].gravity),
+            Some(ValidRange(0.0..25.0).boxed()),
+        ),
+        (
+            ANGULAR_DAMPING,
+            "Fraction of angular velocity retained after each step",
+            Data::F32(0.999),
+            Some(ValidRange(0.9..1.0).boxed()),
+        ),
+        (
+            LANDING_MAX_VERTICAL_SPEED,
+            "Maximum vertical speed at touchdown, in m/s",
+            Data::F32(3.0),
+            Some(ValidRange(0.5..10.0).boxed()),
+        ),
+        (
+            LANDING_MAX_HORIZONTAL_SPEED,
+            "Maximum horizontal speed at touchdown, in m/s",
+            Data::F32(1.0),
+            Some(ValidRange(0.1..5.0).boxed()),
+        ),
+        (
+            LANDING_MAX_TILT,
+            "Maximum tilt from upright at touchdown, in degrees",
+            Data::F32(10.0),
+            Some(ValidRange(1.0..45.0).boxed()),
+        ),
+        (
+            LANDING_MAX_ANGULAR_SPEED,
+            "Maximum angular speed at touchdown, in radians/s",
+            Data::F32(0.25),
+            Some(ValidRange(0.05..2.0).boxed()),
+        ),
+        (
+            SUN_AZIMUTH,
+            "Sun azimuth, in degrees counter-clockwise from the x axis",
+            Data::F32(135.0),
+            Some(ValidRange(0.0..360.0).boxed()),
+        ),
+        (
+            SUN_ELEVATION,
+            "Sun elevation, in degrees above the horizon",
+            Data::F32(30.0),
+            Some(ValidRange(5.0..90.0).boxed()),
+        ),
+        (
+            RADAR_ENABLED,
+            "Fly on simulated landing radar measurements, rather than the true state",
+            Data::Bool(false),
+            None,
+        ),
+        (
+            RADAR_RANGE_NOISE,
+            "Landing radar range noise standard deviation, in meters",
+            Data::F32(0.5),
+            Some(ValidRange(0.0..10.0).boxed()),
+        ),
+        (
+            RADAR_RANGE_BIAS,
+            "Landing radar range bias, in meters",
+            Data::F32(0.0),
+            Some(ValidRange(-10.0..10.0).boxed()),
+        ),
+        (
+            RADAR_VELOCITY_NOISE,
+            "Landing radar velocity noise standard deviation, in meters/s",
+            Data::F32(0.1),
+            Some(ValidRange(0.0..2.0).boxed()),
+        ),
+        (
+            RADAR_LOCK_ON_ALTITUDE,
+            "Altitude below which the landing radar locks onto the surface",
+            Data::F32(600.0),
+            Some(ValidRange(10.0..2000.0).boxed()),
+        ),
+        (
+            RADAR_DROPOUT_RATE,
+            "Landing radar dropouts per second",
+            Data::F32(0.02),
+            Some(ValidRange(0.0..1.0).boxed()),
+        ),
+        (
+            NAV_FILTER_ENABLED,
+            "Fly on a navigation filter fusing the IMU and landing radar",
+            Data::Bool(false),
+            None,
+        ),
+        (
+            IMU_ACCEL_NOISE,
+            "Accelerometer noise standard deviation, in meters/s^2",
+            Data::F32(0.02),
+            Some(ValidRange(0.0..1.0).boxed()),
+        ),
+        (
+            IMU_ACCEL_BIAS,
+            "Accelerometer turn-on bias standard deviation, in meters/s^2",
+            Data::F32(0.005),
+            Some(ValidRange(0.0..0.5).boxed()),
+        ),
+        (
+            IMU_GYRO_NOISE,
+            "Gyro noise standard deviation, in radians/s",
+            Data::F32(0.001),
+            Some(ValidRange(0.0..0.1).boxed()),
+        ),
+        (
+            IMU_GYRO_BIAS,
+            "Gyro turn-on bias standard deviation, in radians/s",
+            Data::F32(0.0002),
+            Some(ValidRange(0.0..0.05).boxed()),
+        ),
+        (
+            FAULT_RANDOM_RATE,
+            "Random faults per minute of flight (0 to disable)",
+            Data::F32(0.0),
+            Some(ValidRange(0.0..10.0).boxed()),
+        ),
+        (
+            FAULT_ENGINE_OUT_TIME,
+            "Seconds into the game to fail the descent engine (0 to disable)",
+            Data::F32(0.0),
+            Some(ValidRange(0.0..600.0).boxed()),
+        ),
+        (
+            FAULT_STUCK_THRUSTER_TIME,
+            "Seconds into the game to stick an RCS thruster on (0 to disable)",
+            Data::F32(0.0),
+            Some(ValidRange(0.0..600.0).boxed()),
+        ),
+        (
+            FAULT_RADAR_FREEZE_TIME,
+            "Seconds into the game to freeze the landing radar (0 to disable)",
+            Data::F32(0.0),
+            Some(ValidRange(0.0..600.0).boxed()),
+        ),
+        (
+            FAULT_FUEL_LEAK_TIME,
+            "Seconds into the game to start leaking fuel (0 to disable)",
+            Data::F32(0.0),
+            Some(ValidRange(0.0..600.0).boxed()),
+        ),
+        (
+            FAULT_FUEL_LEAK_RATE,
+            "Fuel leak rate, in kg/s",
+            Data::F32(2.0),
+            Some(ValidRange(0.0..20.0).boxed()),
+        ),
+        (
+            FUEL_CALLOUTS,
+            "Seconds of fuel remaining at hover throttle for each fuel callout",
+            Data::F32Array(vec![60.0, 30.0]),
+            Some(ValidRange(0.0..120.0).boxed()),
+        ),
+        (
+            FUEL_BINGO,
+            "Seconds of fuel remaining at hover throttle for the bingo fuel land-or-abort call (0 to disable)",
+            Data::F32(20.0),
+            Some(ValidRange(0.0..120.0).boxed()),
+        ),
+        (
+            SCORE_CRITERION_WEIGHT,
+            "Maximum points for each landing criterion",
+            Data::F32(2.0),
+            Some(ValidRange(0.0..10.0).boxed()),
+        ),
+        (
+            SCORE_PRECISION_WEIGHT,
+            "Multiplier for the points for distance from target",
+            Data::F32(2.0),
+            Some(ValidRange(0.0..10.0).boxed()),
+        ),
+        (
+            SCORE_FUEL_WEIGHT,
+            "Points for landing with a full tank of descent fuel",
+            Data::F32(5.0),
+            Some(ValidRange(0.0..20.0).boxed()),
+        ),
+        (
+            SCORE_TIME_PENALTY,
+            "Points deducted for each second of flight",
+            Data::F32(0.02),
+            Some(ValidRange(0.0..1.0).boxed()),
         ),
     ];
     params
@@ -88,123 +585,845 @@ fn default_values() -> HashMap<String, Value> {
         .collect()
 }
 
-#[derive(Default)]
-pub struct Parameters(RwLock<Registry>);
+/// Capacity of the parameter change channel, beyond which slow subscribers miss changes.
+const CHANGES_CAPACITY: usize = 64;
+
+/// A change to parameter values.
+#[derive(Debug, Clone)]
+pub struct Change {
+    /// The updated parameters.
+    pub parameters: Vec<Parameter>,
+    /// Whether the change was requested by a websocket client, rather than made by the game.
+    pub from_client: bool,
+}
+
+/// A subscription to parameter changes.
+pub struct Subscription(broadcast::Receiver<Change>);
+
+impl Subscription {
+    /// Waits for the next change, or returns `None` if the parameters have been dropped.
+    ///
+    /// If the subscriber has fallen behind, the missed changes are skipped.
+    pub async fn recv(&mut self) -> Option<Change> {
+        loop {
+            match self.0.recv().await {
+                Ok(change) => return Some(change),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Returns whether any parameters have changed since the last call, without waiting.
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        loop {
+            match self.0.try_recv() {
+                Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) => changed = true,
+                Err(_) => return changed,
+            }
+        }
+    }
+}
+
+/// Reads the named scenario from `dir`, and checks that it fits the landscape.
+fn read_scenario(dir: &Path, registry: &Registry, name: &str) -> anyhow::Result<Scenario> {
+    let scenario = Scenario::read(&profile::path(dir, name)?)?;
+    if let Some(landing_zone) = scenario.landing_zone() {
+        let width = registry.get_u32(LANDSCAPE_WIDTH).unwrap();
+        let radius = registry.get_u32(LANDING_ZONE_RADIUS).unwrap();
+        let max = (width / 2).saturating_sub(radius + LANDING_ZONE_BLEND_RADIUS) as f32;
+        anyhow::ensure!(
+            landing_zone.length() <= max,
+            "the landing zone must be within {max} m of the center of the landscape"
+        );
+    }
+    Ok(scenario)
+}
+
+/// Returns a registry with the default parameters, and the scenario's parameters applied.
+fn scenario_registry(scenario: &Scenario) -> anyhow::Result<Registry> {
+    let mut registry = Registry::default();
+    let params = registry.parameters_from_json(scenario.parameters.clone());
+    registry.set_parameters(params)?;
+    Ok(registry)
+}
+
+/// Returns true if the parameter affects gameplay, unlike the seed, profile and scenario
+/// parameters.
+fn is_gameplay(name: &str) -> bool {
+    ![SEED, REGENERATE_SEED, SAVE_PROFILE, LOAD_PROFILE, SCENARIO].contains(&name)
+}
+
+/// Parameters that were rejected, with a description of each problem.
+#[derive(Debug)]
+pub struct Rejected(Vec<String>);
+
+impl std::fmt::Display for Rejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rejected parameters: {}", self.0.join("; "))
+    }
+}
+
+impl std::error::Error for Rejected {}
+
+pub struct Parameters {
+    registry: RwLock<Registry>,
+    changes: broadcast::Sender<Change>,
+    /// File that parameters are saved to whenever they change.
+    path: PathBuf,
+    /// Directory containing parameter profiles.
+    profiles_dir: PathBuf,
+    /// Directory containing scenarios.
+    scenarios_dir: PathBuf,
+}
+
+impl Parameters {
+    /// Loads parameters from `path`, or uses the defaults if the file doesn't exist.
+    ///
+    /// Parameters are saved back to `path` whenever they change. Named profiles are stored in
+    /// `profiles_dir`, and scenarios are read from `scenarios_dir`.
+    pub fn load(path: &Path, profiles_dir: &Path, scenarios_dir: &Path) -> anyhow::Result<Self> {
+        let mut registry = Registry::default();
+        registry.set_files(LOAD_PROFILE, profile::list(profiles_dir));
+        registry.set_files(SCENARIO, profile::list(scenarios_dir));
+        if path.exists() {
+            let values = profile::read(path).context("failed to load parameters")?;
+            if let Err(e) = registry.set_parameters(registry.parameters_from_json(values)) {
+                eprintln!("ignoring saved parameters: {e}");
+            }
+        }
+        Ok(Self {
+            registry: RwLock::new(registry),
+            changes: broadcast::channel(CHANGES_CAPACITY).0,
+            path: path.to_path_buf(),
+            profiles_dir: profiles_dir.to_path_buf(),
+            scenarios_dir: scenarios_dir.to_path_buf(),
+        })
+    }
+
+    /// Subscribes to parameter changes, whether they're requested by a websocket client or made
+    /// by the game.
+    pub fn subscribe(&self) -> Subscription {
+        Subscription(self.changes.subscribe())
+    }
+
+    /// Notifies subscribers of updated parameters, if there are any.
+    fn notify(&self, parameters: Vec<Parameter>, from_client: bool) {
+        if !parameters.is_empty() {
+            // It's fine if nobody is listening.
+            let _ = self.changes.send(Change {
+                parameters,
+                from_client,
+            });
+        }
+    }
+
+    /// Rescans the profile and scenario directories for files to select from.
+    ///
+    /// If the selected profile or scenario no longer exists, it's deselected.
+    pub fn scan_files(&self) {
+        let mut registry = self.registry.write();
+        let updated = self.scan_files_locked(&mut registry);
+        drop(registry);
+        self.notify(updated, false);
+    }
+
+    fn scan_files_locked(&self, registry: &mut Registry) -> Vec<Parameter> {
+        let mut updated = vec![];
+        for (key, dir) in [
+            (LOAD_PROFILE, &self.profiles_dir),
+            (SCENARIO, &self.scenarios_dir),
+        ] {
+            if registry.set_files(key, profile::list(dir)) {
+                updated.extend(registry.get_parameters([key]));
+            }
+        }
+        updated
+    }
+
+    /// Returns the current seed, without regenerating it.
+    pub fn seed(&self) -> u64 {
+        self.registry.read().get_seed(SEED).unwrap()
+    }
+
+    /// Returns the seed for a new game, regenerating it first if `regenerate_seed` is set.
+    pub fn next_seed(&self) -> u64 {
+        let mut registry = self.registry.write();
+        if registry.get_bool(REGENERATE_SEED).unwrap() {
+            let seed = rand::random_range(0..=MAX_SAFE_INTEGER);
+            registry.set_seed(SEED, seed);
+            let updated = registry.get_parameters([SEED]);
+            drop(registry);
+            self.notify(updated, false);
+            seed
+        } else {
+            registry.get_seed(SEED).unwrap()
+        }
+    }
+
+    pub fn landscape_width(&self) -> u32 {
+        self.registry.read().get_u32(LANDSCAPE_WIDTH).unwrap()
+    }
+
+    pub fn landing_zone_min_distance(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(LANDING_ZONE_MIN_DISTANCE)
+            .unwrap()
+    }
+
+    pub fn landing_zone_max_distance(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(LANDING_ZONE_MAX_DISTANCE)
+            .unwrap()
+    }
+
+    pub fn landing_zone_radius(&self) -> u32 {
+        self.registry.read().get_u32(LANDING_ZONE_RADIUS).unwrap()
+    }
+
+    pub fn lander_init_altitude(&self) -> f32 {
+        self.registry.read().get_f32(INIT_ALTITUDE).unwrap()
+    }
+
+    pub fn lander_init_vertical_velocity(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(INIT_VERTICAL_VELOCITY)
+            .unwrap()
+    }
+
+    pub fn lander_init_vertical_velocity_target(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(INIT_VERTICAL_VELOCITY_TARGET)
+            .unwrap()
+    }
+
+    pub fn lander_init_fuel_mass(&self) -> f32 {
+        self.registry.read().get_f32(INIT_FUEL_MASS).unwrap()
+    }
+
+    pub fn lander_init_horizontal_speed_max(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(INIT_HORIZONTAL_SPEED_MAX)
+            .unwrap()
+    }
+
+    pub fn lander_init_tilt_max(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(INIT_TILT_MAX)
+            .unwrap()
+            .to_radians()
+    }
+
+    pub fn lander_init_angular_speed_max(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(INIT_ANGULAR_SPEED_MAX)
+            .unwrap()
+    }
+
+    pub fn powered_descent(&self) -> bool {
+        self.registry.read().get_bool(POWERED_DESCENT).unwrap()
+    }
+
+    pub fn powered_descent_altitude(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(POWERED_DESCENT_ALTITUDE)
+            .unwrap()
+    }
+
+    pub fn powered_descent_range(&self) -> f32 {
+        self.registry.read().get_f32(POWERED_DESCENT_RANGE).unwrap()
+    }
+
+    pub fn powered_descent_speed(&self) -> f32 {
+        self.registry.read().get_f32(POWERED_DESCENT_SPEED).unwrap()
+    }
+
+    pub fn powered_descent_fuel_mass(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(POWERED_DESCENT_FUEL_MASS)
+            .unwrap()
+    }
+
+    pub fn terrain_roughness(&self) -> f32 {
+        self.registry.read().get_f32(TERRAIN_ROUGHNESS).unwrap()
+    }
+
+    pub fn lander_dry_mass(&self) -> f32 {
+        self.registry.read().get_f32(LANDER_DRY_MASS).unwrap()
+    }
+
+    pub fn ascent_stage_dry_mass(&self) -> f32 {
+        self.registry.read().get_f32(ASCENT_STAGE_DRY_MASS).unwrap()
+    }
+
+    pub fn ascent_stage_fuel_mass(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(ASCENT_STAGE_FUEL_MASS)
+            .unwrap()
+    }
+
+    pub fn descent_engine_thrust(&self) -> f32 {
+        self.registry.read().get_f32(DESCENT_ENGINE_THRUST).unwrap()
+    }
+
+    pub fn descent_fuel_burn_rate(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(DESCENT_FUEL_BURN_RATE)
+            .unwrap()
+    }
+
+    pub fn ascent_engine_thrust(&self) -> f32 {
+        self.registry.read().get_f32(ASCENT_ENGINE_THRUST).unwrap()
+    }
+
+    pub fn ascent_fuel_burn_rate(&self) -> f32 {
+        self.registry.read().get_f32(ASCENT_FUEL_BURN_RATE).unwrap()
+    }
+
+    pub fn rcs_thrust(&self) -> f32 {
+        self.registry.read().get_f32(RCS_THRUST).unwrap()
+    }
+
+    pub fn rcs_torque(&self) -> f32 {
+        self.registry.read().get_f32(RCS_TORQUE).unwrap()
+    }
+
+    pub fn gravity(&self) -> f32 {
+        self.registry.read().get_f32(GRAVITY).unwrap()
+    }
+
+    pub fn angular_damping(&self) -> f32 {
+        self.registry.read().get_f32(ANGULAR_DAMPING).unwrap()
+    }
 
-impl Parameters {
-    pub fn next_seed(&self) -> u64 {
-        let mut registry = self.0.write();
-        if registry.get_bool(REGENERATE_SEED).unwrap() {
-            let seed = rand::random();
-            registry.set_seed(SEED, seed);
-            seed
-        } else {
-            registry.get_seed(SEED).unwrap()
-        }
+    pub fn landing_max_vertical_speed(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(LANDING_MAX_VERTICAL_SPEED)
+            .unwrap()
     }
 
-    pub fn landscape_width(&self) -> u32 {
-        self.0.read().get_f32(LANDSCAPE_WIDTH).unwrap() as u32
+    pub fn landing_max_horizontal_speed(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(LANDING_MAX_HORIZONTAL_SPEED)
+            .unwrap()
     }
 
-    pub fn landing_zone_min_distance(&self) -> f32 {
-        self.0.read().get_f32(LANDING_ZONE_MIN_DISTANCE).unwrap()
+    /// Maximum tilt from upright at touchdown, in radians.
+    pub fn landing_max_tilt(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(LANDING_MAX_TILT)
+            .unwrap()
+            .to_radians()
     }
 
-    pub fn landing_zone_max_distance(&self) -> f32 {
-        self.0.read().get_f32(LANDING_ZONE_MAX_DISTANCE).unwrap()
+    pub fn landing_max_angular_speed(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(LANDING_MAX_ANGULAR_SPEED)
+            .unwrap()
     }
 
-    pub fn landing_zone_radius(&self) -> u32 {
-        self.0.read().get_f32(LANDING_ZONE_RADIUS).unwrap() as u32
+    pub fn approach_glide_slope(&self) -> f32 {
+        self.registry.read().get_f32(APPROACH_GLIDE_SLOPE).unwrap()
     }
 
-    pub fn lander_init_altitude(&self) -> f32 {
-        self.0.read().get_f32(INIT_ALTITUDE).unwrap()
+    /// Returns a unit vector pointing towards the sun.
+    pub fn sun_direction(&self) -> Vec3 {
+        let registry = self.registry.read();
+        let azimuth = registry.get_f32(SUN_AZIMUTH).unwrap().to_radians();
+        let elevation = registry.get_f32(SUN_ELEVATION).unwrap().to_radians();
+        Vec3::new(
+            elevation.cos() * azimuth.cos(),
+            elevation.cos() * azimuth.sin(),
+            elevation.sin(),
+        )
     }
 
-    pub fn lander_init_vertical_velocity(&self) -> f32 {
-        self.0.read().get_f32(INIT_VERTICAL_VELOCITY).unwrap()
+    pub fn radar_enabled(&self) -> bool {
+        self.registry.read().get_bool(RADAR_ENABLED).unwrap()
     }
 
-    pub fn lander_init_vertical_velocity_target(&self) -> f32 {
-        self.0
+    pub fn radar_range_noise(&self) -> f32 {
+        self.registry.read().get_f32(RADAR_RANGE_NOISE).unwrap()
+    }
+
+    pub fn radar_range_bias(&self) -> f32 {
+        self.registry.read().get_f32(RADAR_RANGE_BIAS).unwrap()
+    }
+
+    pub fn radar_velocity_noise(&self) -> f32 {
+        self.registry.read().get_f32(RADAR_VELOCITY_NOISE).unwrap()
+    }
+
+    pub fn radar_lock_on_altitude(&self) -> f32 {
+        self.registry
             .read()
-            .get_f32(INIT_VERTICAL_VELOCITY_TARGET)
+            .get_f32(RADAR_LOCK_ON_ALTITUDE)
+            .unwrap()
+    }
+
+    pub fn radar_dropout_rate(&self) -> f32 {
+        self.registry.read().get_f32(RADAR_DROPOUT_RATE).unwrap()
+    }
+
+    pub fn nav_filter_enabled(&self) -> bool {
+        self.registry.read().get_bool(NAV_FILTER_ENABLED).unwrap()
+    }
+
+    pub fn imu_accel_noise(&self) -> f32 {
+        self.registry.read().get_f32(IMU_ACCEL_NOISE).unwrap()
+    }
+
+    pub fn imu_accel_bias(&self) -> f32 {
+        self.registry.read().get_f32(IMU_ACCEL_BIAS).unwrap()
+    }
+
+    pub fn imu_gyro_noise(&self) -> f32 {
+        self.registry.read().get_f32(IMU_GYRO_NOISE).unwrap()
+    }
+
+    pub fn imu_gyro_bias(&self) -> f32 {
+        self.registry.read().get_f32(IMU_GYRO_BIAS).unwrap()
+    }
+
+    pub fn fault_random_rate(&self) -> f32 {
+        self.registry.read().get_f32(FAULT_RANDOM_RATE).unwrap()
+    }
+
+    pub fn fault_engine_out_time(&self) -> f32 {
+        self.registry.read().get_f32(FAULT_ENGINE_OUT_TIME).unwrap()
+    }
+
+    pub fn fault_stuck_thruster_time(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(FAULT_STUCK_THRUSTER_TIME)
+            .unwrap()
+    }
+
+    pub fn fault_radar_freeze_time(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(FAULT_RADAR_FREEZE_TIME)
+            .unwrap()
+    }
+
+    pub fn fault_fuel_leak_time(&self) -> f32 {
+        self.registry.read().get_f32(FAULT_FUEL_LEAK_TIME).unwrap()
+    }
+
+    pub fn fault_fuel_leak_rate(&self) -> f32 {
+        self.registry.read().get_f32(FAULT_FUEL_LEAK_RATE).unwrap()
+    }
+
+    pub fn fuel_callouts(&self) -> Vec<f32> {
+        self.registry.read().get_f32_array(FUEL_CALLOUTS).unwrap()
+    }
+
+    pub fn fuel_bingo(&self) -> f32 {
+        self.registry.read().get_f32(FUEL_BINGO).unwrap()
+    }
+
+    pub fn score_criterion_weight(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(SCORE_CRITERION_WEIGHT)
+            .unwrap()
+    }
+
+    pub fn score_precision_weight(&self) -> f32 {
+        self.registry
+            .read()
+            .get_f32(SCORE_PRECISION_WEIGHT)
             .unwrap()
     }
 
+    pub fn score_fuel_weight(&self) -> f32 {
+        self.registry.read().get_f32(SCORE_FUEL_WEIGHT).unwrap()
+    }
+
+    pub fn score_time_penalty(&self) -> f32 {
+        self.registry.read().get_f32(SCORE_TIME_PENALTY).unwrap()
+    }
+
+    pub fn vehicle(&self) -> &'static Vehicle {
+        &VEHICLES[self.registry.read().get_enum(VEHICLE).unwrap()]
+    }
+
+    pub fn body(&self) -> &'static Body {
+        &BODIES[self.registry.read().get_enum(BODY).unwrap()]
+    }
+
+    /// Reads the selected scenario, if there is one.
+    ///
+    /// The scenario is read afresh for each game, so that edits to the file take effect without
+    /// selecting it again.
+    pub fn scenario(&self) -> anyhow::Result<Option<Scenario>> {
+        let registry = self.registry.read();
+        let Some(name) = registry.get_file(SCENARIO) else {
+            return Ok(None);
+        };
+        read_scenario(&self.scenarios_dir, &registry, &name)
+            .map(Some)
+            .with_context(|| format!("failed to read scenario {name:?}"))
+    }
+
+    /// Returns a label for the difficulty of the game, for the leaderboard.
+    ///
+    /// Games played with the selected difficulty preset, vehicle and body, and every other
+    /// gameplay parameter at its default, are labeled with the name of the preset, followed by
+    /// the vehicle if it isn't the Apollo LM, and the body if it isn't the Moon. All others are
+    /// "custom". The seed parameters don't affect difficulty.
+    ///
+    /// Games played with a scenario, and every gameplay parameter as the scenario sets it, are
+    /// labeled with the scenario's name instead. Games played with a scenario and anything else
+    /// are "custom".
+    pub fn difficulty(&self, scenario: Option<&Scenario>) -> String {
+        let registry = self.registry.read();
+        if let Some(scenario) = scenario {
+            let is_scenario = scenario_registry(scenario).is_ok_and(|expected| {
+                registry
+                    .0
+                    .values()
+                    .filter(|v| is_gameplay(v.name))
+                    .all(|v| v.current == expected.0[v.name].current)
+            });
+            return if is_scenario {
+                format!("scenario {}", scenario.name)
+            } else {
+                "custom".into()
+            };
+        }
+        let index = registry.get_enum(DIFFICULTY).unwrap();
+        let vehicle = registry.get_enum(VEHICLE).unwrap();
+        let body = registry.get_enum(BODY).unwrap();
+        let preset: HashMap<_, _> = difficulty_preset(index)
+            .into_iter()
+            .chain(vehicle_preset(vehicle))
+            .chain(body_preset(body))
+            .collect();
+        let is_preset = registry
+            .0
+            .values()
+            .filter(|v| is_gameplay(v.name))
+            .filter(|v| ![DIFFICULTY, VEHICLE, BODY].contains(&v.name))
+            .all(|v| v.current == *preset.get(v.name).unwrap_or(&v.default));
+        if !is_preset {
+            return "custom".into();
+        }
+        let mut label = DIFFICULTY_PRESETS[index].to_string();
+        if vehicle != 0 {
+            label = format!("{label} {}", VEHICLE_NAMES[vehicle]);
+        }
+        if body != 0 {
+            label = format!("{label} {}", BODY_NAMES[body]);
+        }
+        label
+    }
+
+    /// Writes a JSON schema describing the parameters, as saved to parameter files, to `path`.
+    pub fn write_schema(&self, path: &Path) -> anyhow::Result<()> {
+        profile::write(path, &self.registry.read().json_schema())
+            .context("failed to write parameter schema")
+    }
+
     pub fn get(&self, names: impl IntoIterator<Item = impl AsRef<str>>) -> Vec<Parameter> {
-        self.0.read().get_parameters(names)
+        self.registry.read().get_parameters(names)
     }
 
-    pub fn set(&self, params: Vec<Parameter>) -> Vec<Parameter> {
-        self.0.write().set_parameters(params)
+    /// Sets parameters on behalf of a websocket client, and returns the updated parameters.
+    ///
+    /// Returns an error if the parameters are rejected, or if saving or loading a profile or
+    /// scenario fails, in which case none of them are set. A profile that was saved before a
+    /// later failure stays saved.
+    pub fn set(&self, params: Vec<Parameter>) -> anyhow::Result<Vec<Parameter>> {
+        let mut registry = self.registry.write();
+        let snapshot = registry.snapshot();
+        let mut updated = registry.set_parameters(params)?;
+
+        // Saving and loading profiles are actions, triggered by setting the profile name.
+        let mut errors = vec![];
+        if let Some(name) = registry
+            .get_text(SAVE_PROFILE)
+            .filter(|_| updated.iter().any(|p| p.name == SAVE_PROFILE))
+        {
+            let result = profile::path(&self.profiles_dir, &name)
+                .and_then(|path| profile::write(&path, &registry.to_json()))
+                .with_context(|| format!("failed to save profile {name:?}"));
+            match result {
+                Ok(()) => updated.extend(self.scan_files_locked(&mut registry)),
+                Err(e) => errors.push(format!("{e:#}")),
+            }
+        }
+        if let Some(name) = registry
+            .get_file(LOAD_PROFILE)
+            .filter(|_| updated.iter().any(|p| p.name == LOAD_PROFILE))
+        {
+            let result = profile::path(&self.profiles_dir, &name)
+                .and_then(|path| profile::read(&path))
+                .and_then(|values| {
+                    let params = registry.parameters_from_json(values);
+                    Ok(registry.set_parameters(params)?)
+                })
+                .with_context(|| format!("failed to load profile {name:?}"));
+            match result {
+                Ok(loaded) => updated.extend(loaded),
+                Err(e) => errors.push(format!("{e:#}")),
+            }
+        }
+
+        // Selecting a scenario applies its parameters, on top of the defaults.
+        if let Some(name) = registry
+            .get_file(SCENARIO)
+            .filter(|_| updated.iter().any(|p| p.name == SCENARIO))
+        {
+            let result = profile::path(&self.scenarios_dir, &name)
+                .and_then(|path| Scenario::read(&path))
+                .and_then(|scenario| {
+                    let params = scenario_registry(&scenario)?.gameplay_parameters();
+                    let applied = registry.set_parameters(params)?;
+                    read_scenario(&self.scenarios_dir, &registry, &name)?;
+                    Ok(applied)
+                })
+                .with_context(|| format!("failed to load scenario {name:?}"));
+            match result {
+                Ok(applied) => updated.extend(applied),
+                Err(e) => errors.push(format!("{e:#}")),
+            }
+        }
+
+        if !errors.is_empty() {
+            registry.restore(snapshot);
+            return Err(Rejected(errors).into());
+        }
+        if let Err(e) = profile::write(&self.path, &registry.to_json()) {
+            eprintln!("failed to save parameters: {e:#}");
+        }
+        drop(registry);
+
+        // A parameter can be updated more than once, e.g. by a preset and then a profile, so only
+        // keep its last value.
+        let mut seen = HashSet::new();
+        updated.reverse();
+        updated.retain(|p| seen.insert(p.name.clone()));
+        updated.reverse();
+        self.notify(updated.clone(), true);
+        Ok(updated)
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq)]
 enum Data {
+    /// A seed, which is at most `MAX_SAFE_INTEGER`, so that it's exposed exactly as a float64.
     Seed(u64),
     Bool(bool),
+    U32(u32),
     F32(f32),
+    F32Array(Vec<f32>),
+    /// An index into a list of variant names.
+    Enum(usize, Arc<[String]>),
+    Text(String),
 }
 impl Data {
     fn parameter_type(&self) -> Option<ParameterType> {
-        None
+        match self {
+            Data::Text(_) => Some(ParameterType::ByteArray),
+            Data::Bool(_) => None,
+            Data::Seed(_) | Data::U32(_) | Data::F32(_) | Data::Enum(..) => {
+                Some(ParameterType::Float64)
+            }
+            Data::F32Array(_) => Some(ParameterType::Float64Array),
+        }
     }
 
     fn as_parameter_value(&self) -> Option<ParameterValue> {
         match self {
-            Data::Seed(v) => Some(ParameterValue::String(v.to_le_bytes().to_vec())),
+            Data::Seed(v) => Some(ParameterValue::Number(*v as f64)),
             Data::Bool(v) => Some(ParameterValue::Bool(*v)),
+            Data::U32(v) => Some(ParameterValue::Number((*v).into())),
             Data::F32(v) => Some(ParameterValue::Number((*v).into())),
+            Data::F32Array(v) => Some(ParameterValue::Array(
+                v.iter()
+                    .map(|v| ParameterValue::Number((*v).into()))
+                    .collect(),
+            )),
+            Data::Enum(index, _) => Some(ParameterValue::Number(*index as f64)),
+            Data::Text(v) => Some(ParameterValue::String(v.as_bytes().to_vec())),
         }
     }
-}
 
-trait Clamp: Send + Sync + 'static {
-    fn clamp(&self, registry: &Registry, data: &Data) -> Option<Data>;
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Data::Seed(v) => (*v).into(),
+            Data::Bool(v) => (*v).into(),
+            Data::U32(v) => (*v).into(),
+            Data::F32(v) => (*v).into(),
+            Data::F32Array(v) => v.as_slice().into(),
+            Data::Enum(index, names) => names[*index].as_str().into(),
+            Data::Text(v) => v.as_str().into(),
+        }
+    }
+
+    /// Converts a JSON value to a parameter value of the same type as this one.
+    fn parameter_value_from_json(&self, json: &serde_json::Value) -> Option<ParameterValue> {
+        match (self, json) {
+            (Data::Seed(_), serde_json::Value::Number(v)) => {
+                let seed = v.as_u64().filter(|seed| *seed <= MAX_SAFE_INTEGER)?;
+                Some(ParameterValue::Number(seed as f64))
+            }
+            (Data::Bool(_), serde_json::Value::Bool(v)) => Some(ParameterValue::Bool(*v)),
+            (Data::U32(_) | Data::F32(_) | Data::Enum(..), serde_json::Value::Number(v)) => {
+                Some(ParameterValue::Number(v.as_f64()?))
+            }
+            (Data::F32Array(_), serde_json::Value::Array(v)) => Some(ParameterValue::Array(
+                v.iter()
+                    .map(|v| Some(ParameterValue::Number(v.as_f64()?)))
+                    .collect::<Option<_>>()?,
+            )),
+            (Data::Enum(_, names), serde_json::Value::String(v)) => {
+                let index = names.iter().position(|name| name == v)?;
+                Some(ParameterValue::Number(index as f64))
+            }
+            (Data::Text(_), serde_json::Value::String(v)) => {
+                Some(ParameterValue::String(v.as_bytes().to_vec()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a JSON schema for values of the same type as this one, as saved to parameter
+    /// files, limited to `range` if there is one.
+    fn json_schema(&self, range: Option<(f64, f64)>) -> serde_json::Value {
+        let number = |ty: &str| match range {
+            Some((min, max)) => json!({ "type": ty, "minimum": min, "maximum": max }),
+            None => json!({ "type": ty }),
+        };
+        match self {
+            Data::Seed(_) => {
+                json!({ "type": "integer", "minimum": 0, "maximum": MAX_SAFE_INTEGER })
+            }
+            Data::Bool(_) => json!({ "type": "boolean" }),
+            Data::U32(_) => number("integer"),
+            Data::F32(_) => number("number"),
+            Data::F32Array(_) => json!({ "type": "array", "items": number("number") }),
+            Data::Enum(_, names) => json!({ "enum": names.as_ref() }),
+            Data::Text(_) => json!({ "type": "string" }),
+        }
+    }
 }
 
-struct ClampFn<F>(F)
-where
-    F: Fn(&Registry, &Data) -> Option<Data>;
+/// Checks a new value for a single parameter.
+///
+/// Constraints across parameters are checked by `check_constraints`.
+trait Validate: Send + Sync + 'static {
+    fn validate(&self, data: &Data) -> Result<(), String>;
+
+    /// Returns the fixed range that values must be within, if there is one.
+    fn range(&self) -> Option<(f64, f64)> {
+        None
+    }
+}
 
-impl<F> ClampFn<F>
+/// Requires values to be within a range, including both ends.
+struct ValidRange<T>(Range<T>);
+impl<T> ValidRange<T>
 where
-    F: Fn(&Registry, &Data) -> Option<Data> + Send + Sync + 'static,
+    ValidRange<T>: Validate,
 {
-    fn boxed(self) -> Box<dyn Clamp> {
+    fn boxed(self) -> Box<dyn Validate> {
         Box::new(self)
     }
 }
-
-impl<F> Clamp for ClampFn<F>
+impl<T> ValidRange<T>
 where
-    F: Fn(&Registry, &Data) -> Option<Data> + Send + Sync + 'static,
+    T: PartialOrd + Copy + std::fmt::Display,
 {
-    fn clamp(&self, registry: &Registry, data: &Data) -> Option<Data> {
-        self.0(registry, data)
+    fn check(&self, val: T) -> Result<(), String> {
+        let Range { start, end } = self.0;
+        if start <= val && val <= end {
+            Ok(())
+        } else {
+            Err(format!("{val} is outside the range {start} to {end}"))
+        }
     }
 }
+impl Validate for ValidRange<f32> {
+    fn validate(&self, data: &Data) -> Result<(), String> {
+        match data {
+            Data::F32(val) => self.check(*val),
+            Data::F32Array(vals) => vals.iter().try_for_each(|val| self.check(*val)),
+            _ => Ok(()),
+        }
+    }
 
-struct ClampRange<T>(Range<T>);
-impl<T> ClampRange<T>
-where
-    ClampRange<T>: Clamp,
-{
-    fn boxed(self) -> Box<dyn Clamp> {
-        Box::new(self)
+    fn range(&self) -> Option<(f64, f64)> {
+        Some((self.0.start.into(), self.0.end.into()))
     }
 }
-impl Clamp for ClampRange<f32> {
-    fn clamp(&self, _: &Registry, data: &Data) -> Option<Data> {
+impl Validate for ValidRange<u32> {
+    fn validate(&self, data: &Data) -> Result<(), String> {
         match data {
-            Data::F32(val) => Some(Data::F32(val.clamp(self.0.start, self.0.end))),
-            _ => None,
+            Data::U32(val) => self.check(*val),
+            _ => Ok(()),
         }
     }
+
+    fn range(&self) -> Option<(f64, f64)> {
+        Some((self.0.start.into(), self.0.end.into()))
+    }
+}
+
+/// Checks constraints across parameters, returning a description of each one that's violated.
+fn check_constraints(registry: &Registry) -> Vec<String> {
+    let mut errors = vec![];
+    let width = registry.get_u32(LANDSCAPE_WIDTH).unwrap() as f32;
+    let radius = registry.get_u32(LANDING_ZONE_RADIUS).unwrap() as f32;
+    let min_distance = registry.get_f32(LANDING_ZONE_MIN_DISTANCE).unwrap();
+    let max_distance = registry.get_f32(LANDING_ZONE_MAX_DISTANCE).unwrap();
+    if min_distance >= max_distance {
+        errors.push(format!(
+            "{LANDING_ZONE_MIN_DISTANCE} ({min_distance}) must be less than \
+             {LANDING_ZONE_MAX_DISTANCE} ({max_distance})"
+        ));
+    }
+    let max_extent = width / 2.0 - LANDING_ZONE_BLEND_RADIUS as f32;
+    if max_distance + radius > max_extent {
+        errors.push(format!(
+            "{LANDING_ZONE_MAX_DISTANCE} ({max_distance}) plus {LANDING_ZONE_RADIUS} ({radius}) \
+             must be at most {max_extent} for the landing zone to fit in a {LANDSCAPE_WIDTH} of \
+             {width}"
+        ));
+    }
+    // The powered descent has to start below orbital speed, or the lander would never come down.
+    if registry.get_bool(POWERED_DESCENT).unwrap() {
+        let speed = registry.get_f32(POWERED_DESCENT_SPEED).unwrap();
+        let gravity = registry.get_f32(GRAVITY).unwrap();
+        let body = &BODIES[registry.get_enum(BODY).unwrap()];
+        let orbital_speed = (gravity * body.radius).sqrt();
+        if speed >= orbital_speed {
+            errors.push(format!(
+                "{POWERED_DESCENT_SPEED} ({speed}) must be less than the orbital speed of \
+                 {orbital_speed:.0} m/s for a {GRAVITY} of {gravity} on the {}",
+                body.name
+            ));
+        }
+    }
+    errors
 }
 
 struct Value {
@@ -212,7 +1431,7 @@ struct Value {
     descr: &'static str,
     current: Data,
     default: Data,
-    clamp: Option<Box<dyn Clamp>>,
+    valid: Option<Box<dyn Validate>>,
 }
 impl Debug for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -238,14 +1457,14 @@ impl Value {
         name: &'static str,
         descr: &'static str,
         default: Data,
-        clamp: Option<Box<dyn Clamp>>,
+        valid: Option<Box<dyn Validate>>,
     ) -> Self {
         Self {
             name,
             descr,
-            current: default,
+            current: default.clone(),
             default,
-            clamp,
+            valid,
         }
     }
 
@@ -257,38 +1476,103 @@ impl Value {
         }
     }
 
-    fn get_update_from_parameter(
-        &self,
-        registry: &Registry,
-        parameter: &Parameter,
-    ) -> Option<Data> {
+    /// Returns the new value for the parameter, or an error if it has the wrong type or is
+    /// invalid.
+    fn get_update_from_parameter(&self, parameter: &Parameter) -> Result<Data, String> {
+        // Reject NaN and infinities up front, since they slip through range and index checks.
+        let finite = match &parameter.value {
+            Some(ParameterValue::Number(v)) => v.is_finite(),
+            Some(ParameterValue::Array(vs)) => vs
+                .iter()
+                .all(|v| !matches!(v, ParameterValue::Number(v) if !v.is_finite())),
+            _ => true,
+        };
+        if !finite {
+            return Err(format!("{}: expected a finite number", self.name));
+        }
         let updated = match (&self.current, &parameter.value) {
-            (_, None) => self.default,
-            (Data::Seed(_), Some(ParameterValue::String(v))) if v.len() == 8 => {
+            (_, None) => self.default.clone(),
+            (Data::Seed(_), Some(ParameterValue::Number(v)))
+                if *v >= 0.0 && v.fract() == 0.0 && *v <= MAX_SAFE_INTEGER as f64 =>
+            {
+                Data::Seed(*v as u64)
+            }
+            // Seeds may also be given as 8 little-endian bytes, as they used to be exposed.
+            (Data::Seed(_), Some(ParameterValue::String(v)))
+                if v.len() == 8 && v.as_slice().get_u64_le() <= MAX_SAFE_INTEGER =>
+            {
                 Data::Seed(v.as_slice().get_u64_le())
             }
+            (Data::Seed(_), Some(ParameterValue::Number(_) | ParameterValue::String(_))) => {
+                return Err(format!(
+                    "{}: expected an integer from 0 to {MAX_SAFE_INTEGER}",
+                    self.name
+                ));
+            }
             (Data::Bool(_), Some(ParameterValue::Bool(v))) => Data::Bool(*v),
+            (Data::U32(_), Some(ParameterValue::Number(v))) => Data::U32(v.round() as u32),
             (Data::F32(_), Some(ParameterValue::Number(v))) => Data::F32(*v as f32),
-            _ => return None,
+            (Data::F32Array(_), Some(ParameterValue::Array(vs))) => Data::F32Array(
+                vs.iter()
+                    .map(|v| match v {
+                        ParameterValue::Number(v) => Ok(*v as f32),
+                        _ => Err(format!("{}: expected an array of numbers", self.name)),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            (Data::Enum(_, names), Some(ParameterValue::Number(v))) => {
+                let index = v.round() as usize;
+                if *v < 0.0 || index >= names.len() {
+                    return Err(format!(
+                        "{}: {v} is not one of 0 to {}",
+                        self.name,
+                        names.len() - 1
+                    ));
+                }
+                Data::Enum(index, names.clone())
+            }
+            (Data::Enum(_, names), Some(ParameterValue::String(v))) => {
+                let index = names
+                    .iter()
+                    .position(|name| name.as_bytes() == v)
+                    .ok_or_else(|| {
+                        format!("{}: expected one of {}", self.name, names.join(", "))
+                    })?;
+                Data::Enum(index, names.clone())
+            }
+            (Data::Text(_), Some(ParameterValue::String(v))) => Data::Text(
+                String::from_utf8(v.clone())
+                    .map_err(|_| format!("{}: invalid UTF-8", self.name))?,
+            ),
+            _ => return Err(format!("{}: wrong type", self.name)),
         };
-        let clamped = self
-            .clamp
-            .as_ref()
-            .and_then(|c| c.clamp(registry, &updated))
-            .unwrap_or(updated);
-        Some(clamped)
+        if let Some(valid) = &self.valid {
+            valid
+                .validate(&updated)
+                .map_err(|e| format!("{}: {e}", self.name))?;
+        }
+        Ok(updated)
     }
 
     fn update(&mut self, data: Data) {
         match (&mut self.current, data) {
             (Data::Seed(p), Data::Seed(v)) => *p = v,
             (Data::Bool(p), Data::Bool(v)) => *p = v,
+            (Data::U32(p), Data::U32(v)) => *p = v,
             (Data::F32(p), Data::F32(v)) => *p = v,
+            (Data::F32Array(p), Data::F32Array(v)) => *p = v,
+            (Data::Enum(p, _), Data::Enum(v, _)) => *p = v,
+            (Data::Text(p), Data::Text(v)) => *p = v,
             _ => panic!("cannot change data type"),
         }
     }
 }
 
+/// Returns variant names for an enum parameter.
+fn variants(names: &[&str]) -> Arc<[String]> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
 struct Registry(HashMap<String, Value>);
 impl Default for Registry {
     fn default() -> Self {
@@ -298,7 +1582,7 @@ impl Default for Registry {
 
 impl Registry {
     fn get_data(&self, key: &str) -> Option<Data> {
-        self.0.get(key).map(|v| v.current)
+        self.0.get(key).map(|v| v.current.clone())
     }
 
     fn get_seed(&self, key: &str) -> Option<u64> {
@@ -315,6 +1599,13 @@ impl Registry {
         })
     }
 
+    fn get_u32(&self, key: &str) -> Option<u32> {
+        self.get_data(key).and_then(|d| match d {
+            Data::U32(v) => Some(v),
+            _ => None,
+        })
+    }
+
     fn get_f32(&self, key: &str) -> Option<f32> {
         self.get_data(key).and_then(|d| match d {
             Data::F32(v) => Some(v),
@@ -322,14 +1613,135 @@ impl Registry {
         })
     }
 
+    fn get_f32_array(&self, key: &str) -> Option<Vec<f32>> {
+        self.get_data(key).and_then(|d| match d {
+            Data::F32Array(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    fn get_enum(&self, key: &str) -> Option<usize> {
+        self.get_data(key).and_then(|d| match d {
+            Data::Enum(v, _) => Some(v),
+            _ => None,
+        })
+    }
+
+    fn get_text(&self, key: &str) -> Option<String> {
+        self.get_data(key).and_then(|d| match d {
+            Data::Text(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns the name of the selected file, or `None` if none is selected.
+    fn get_file(&self, key: &str) -> Option<String> {
+        self.get_data(key).and_then(|d| match d {
+            Data::Enum(index, names) if index > 0 => Some(names[index].clone()),
+            _ => None,
+        })
+    }
+
+    /// Replaces the files to select from, keeping the selected file if it's still there, and
+    /// returns whether the selected index changed.
+    fn set_files(&mut self, key: &str, files: Vec<String>) -> bool {
+        let Some(value) = self.0.get_mut(key) else {
+            return false;
+        };
+        let Data::Enum(index, names) = &mut value.current else {
+            return false;
+        };
+        let files: Arc<[String]> = std::iter::once(NONE.to_string()).chain(files).collect();
+        let selected = files.iter().position(|f| *f == names[*index]).unwrap_or(0);
+        let changed = selected != *index;
+        value.current = Data::Enum(selected, files.clone());
+        value.default = Data::Enum(0, files);
+        changed
+    }
+
+    /// Returns a copy of every parameter's current and default values, to restore if a change
+    /// fails partway through.
+    fn snapshot(&self) -> Vec<(String, Data, Data)> {
+        self.0
+            .iter()
+            .map(|(name, v)| (name.clone(), v.current.clone(), v.default.clone()))
+            .collect()
+    }
+
+    fn restore(&mut self, snapshot: Vec<(String, Data, Data)>) {
+        for (name, current, default) in snapshot {
+            let value = self.0.get_mut(&name).unwrap();
+            value.current = current;
+            value.default = default;
+        }
+    }
+
     fn set_seed(&mut self, key: &str, seed: u64) {
-        if let Some(value) = self.0.get_mut(key) {
-            if let Data::Seed(v) = &mut value.current {
-                *v = seed;
-            }
+        if let Some(value) = self.0.get_mut(key)
+            && let Data::Seed(v) = &mut value.current
+        {
+            *v = seed;
         }
     }
 
+    /// Returns the current parameter values as JSON, excluding the profile actions.
+    fn to_json(&self) -> BTreeMap<String, serde_json::Value> {
+        self.0
+            .values()
+            .filter(|v| ![SAVE_PROFILE, LOAD_PROFILE].contains(&v.name))
+            .map(|v| (v.name.to_string(), v.current.to_json()))
+            .collect()
+    }
+
+    /// Returns all of the gameplay parameters, as websocket parameters.
+    fn gameplay_parameters(&self) -> Vec<Parameter> {
+        self.get_parameters(self.0.keys().filter(|name| is_gameplay(name)))
+    }
+
+    /// Returns a JSON schema for parameter files, as produced by `to_json`.
+    fn json_schema(&self) -> serde_json::Value {
+        let properties: BTreeMap<_, _> = self
+            .0
+            .values()
+            .filter(|v| ![SAVE_PROFILE, LOAD_PROFILE].contains(&v.name))
+            .map(|v| {
+                let range = v.valid.as_ref().and_then(|c| c.range());
+                let mut schema = v.default.json_schema(range);
+                schema["description"] = v.descr.into();
+                schema["default"] = v.default.to_json();
+                (v.name.to_string(), schema)
+            })
+            .collect();
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "fg-lander parameters",
+            "type": "object",
+            "properties": properties,
+            "additionalProperties": false,
+        })
+    }
+
+    /// Converts JSON parameter values to websocket parameters, ignoring any that are unknown or
+    /// of the wrong type.
+    fn parameters_from_json(&self, values: BTreeMap<String, serde_json::Value>) -> Vec<Parameter> {
+        values
+            .into_iter()
+            .filter(|(name, _)| ![SAVE_PROFILE, LOAD_PROFILE].contains(&name.as_str()))
+            .filter_map(|(name, json)| {
+                let value = self
+                    .0
+                    .get(&name)?
+                    .current
+                    .parameter_value_from_json(&json)?;
+                Some(Parameter {
+                    name,
+                    r#type: None,
+                    value: Some(value),
+                })
+            })
+            .collect()
+    }
+
     /// Retruns a vec of websocket parameters.
     fn get_parameters(&self, keys: impl IntoIterator<Item = impl AsRef<str>>) -> Vec<Parameter> {
         let keys: Vec<_> = keys.into_iter().collect();
@@ -342,22 +1754,242 @@ impl Registry {
             .collect()
     }
 
-    fn set_parameters(&mut self, params: Vec<Parameter>) -> Vec<Parameter> {
+    /// Sets parameters, and returns the updated parameters.
+    ///
+    /// If any of the parameters are invalid, or the result would violate a constraint across
+    /// parameters, none of them are set, and the problems are returned instead.
+    fn set_parameters(&mut self, params: Vec<Parameter>) -> Result<Vec<Parameter>, Rejected> {
         let mut updates = Vec::with_capacity(params.len());
+        let mut errors = vec![];
         for param in params {
-            if let Some(value) = self.0.get(&param.name) {
-                if let Some(data) = value.get_update_from_parameter(self, &param) {
-                    updates.push((param.name, data));
+            match self.0.get(&param.name) {
+                Some(value) => match value.get_update_from_parameter(&param) {
+                    Ok(data) => updates.push((param.name, data)),
+                    Err(e) => errors.push(e),
+                },
+                None => errors.push(format!("{}: unknown parameter", param.name)),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(Rejected(errors));
+        }
+        // Selecting a difficulty preset, vehicle or body updates all of the parameters that it
+        // covers, unless they're set explicitly alongside it.
+        let mut presets = vec![];
+        for (name, data) in &updates {
+            match data {
+                Data::Enum(index, _) if name == DIFFICULTY => {
+                    presets.extend(difficulty_preset(*index))
                 }
+                Data::Enum(index, _) if name == VEHICLE => presets.extend(vehicle_preset(*index)),
+                Data::Enum(index, _) if name == BODY => presets.extend(body_preset(*index)),
+                _ => (),
             }
         }
-        updates
+        let presets = presets
             .into_iter()
-            .map(|(name, data)| {
-                let value = self.0.get_mut(&name).unwrap();
-                value.update(data);
-                value.as_parameter()
-            })
-            .collect()
+            .map(|(name, data)| (name.to_string(), data));
+        updates.splice(0..0, presets);
+        // Apply the updates, and roll them back if they violate any constraints.
+        let previous: Vec<_> = updates
+            .iter()
+            .map(|(name, _)| (name.clone(), self.0[name].current.clone()))
+            .collect();
+        for (name, data) in &updates {
+            self.0.get_mut(name).unwrap().update(data.clone());
+        }
+        let errors = check_constraints(self);
+        if !errors.is_empty() {
+            for (name, data) in previous.into_iter().rev() {
+                self.0.get_mut(&name).unwrap().update(data);
+            }
+            return Err(Rejected(errors));
+        }
+        Ok(updates
+            .iter()
+            .map(|(name, _)| self.0[name].as_parameter())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(name: &str, value: f64) -> Parameter {
+        Parameter {
+            name: name.to_string(),
+            r#type: None,
+            value: Some(ParameterValue::Number(value)),
+        }
+    }
+
+    #[test]
+    fn test_reject_non_finite() {
+        let mut registry = Registry::default();
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(
+                registry
+                    .set_parameters(vec![number(DIFFICULTY, value)])
+                    .is_err()
+            );
+            assert!(
+                registry
+                    .set_parameters(vec![number(GRAVITY, value)])
+                    .is_err()
+            );
+        }
+        assert_eq!(registry.get_enum(DIFFICULTY), Some(1));
+    }
+
+    #[test]
+    fn test_reject_min_distance_above_max() {
+        let mut registry = Registry::default();
+        let result = registry.set_parameters(vec![
+            number(LANDING_ZONE_MIN_DISTANCE, 50.0),
+            number(LANDING_ZONE_MAX_DISTANCE, 40.0),
+        ]);
+        let Err(Rejected(errors)) = result else {
+            panic!("expected the parameters to be rejected");
+        };
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with(LANDING_ZONE_MIN_DISTANCE));
+        assert_eq!(registry.get_f32(LANDING_ZONE_MIN_DISTANCE), Some(30.0));
+        assert_eq!(registry.get_f32(LANDING_ZONE_MAX_DISTANCE), Some(70.0));
+    }
+
+    #[test]
+    fn test_preset_with_override() {
+        let mut registry = Registry::default();
+        registry
+            .set_parameters(vec![
+                number(LANDING_MAX_TILT, 12.0),
+                number(DIFFICULTY, 0.0),
+            ])
+            .unwrap();
+        // The explicit value wins over the preset, which sets everything else.
+        assert_eq!(registry.get_f32(LANDING_MAX_TILT), Some(12.0));
+        assert_eq!(registry.get_f32(LANDING_MAX_VERTICAL_SPEED), Some(5.0));
+        assert_eq!(registry.get_f32(INIT_FUEL_MASS), Some(1000.0));
+    }
+
+    #[test]
+    fn test_rejected_batch_leaves_registry_unchanged() {
+        let mut registry = Registry::default();
+        let before = registry.to_json();
+        // The preset is valid on its own, but the batch pushes the landing zone off the map.
+        let result = registry.set_parameters(vec![
+            number(DIFFICULTY, 2.0),
+            number(GRAVITY, 3.0),
+            number(LANDSCAPE_WIDTH, 100.0),
+        ]);
+        assert!(result.is_err());
+        assert_eq!(registry.to_json(), before);
+    }
+
+    #[test]
+    fn test_reject_powered_descent_above_orbital_speed() {
+        let mut registry = Registry::default();
+        let enable = Parameter {
+            name: POWERED_DESCENT.to_string(),
+            r#type: None,
+            value: Some(ParameterValue::Bool(true)),
+        };
+        // The asteroid's preset starts the powered descent below its orbital speed.
+        registry
+            .set_parameters(vec![enable.clone(), number(BODY, 2.0)])
+            .unwrap();
+        assert_eq!(registry.get_f32(POWERED_DESCENT_SPEED), Some(20.0));
+        let result = registry.set_parameters(vec![number(POWERED_DESCENT_SPEED, 300.0)]);
+        assert!(result.is_err());
+        assert_eq!(registry.get_f32(POWERED_DESCENT_SPEED), Some(20.0));
+    }
+
+    /// Returns parameters stored in a temporary directory, with the given profiles.
+    fn parameters_with_profiles(dir: &Path, profiles: &[(&str, &str)]) -> Parameters {
+        let profiles_dir = dir.join("profiles");
+        std::fs::create_dir(&profiles_dir).unwrap();
+        for (name, json) in profiles {
+            std::fs::write(profiles_dir.join(format!("{name}.json")), json).unwrap();
+        }
+        Parameters::load(&dir.join("parameters.json"), &profiles_dir, dir).unwrap()
+    }
+
+    #[test]
+    fn test_failed_profile_load_sets_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let params = parameters_with_profiles(dir.path(), &[("bad", r#"{"gravity": -5}"#)]);
+        let mut changes = params.subscribe();
+        let before = params.get([GRAVITY, LANDING_MAX_TILT, LOAD_PROFILE]);
+        let result = params.set(vec![
+            number(LANDING_MAX_TILT, 12.0),
+            number(LOAD_PROFILE, 1.0),
+        ]);
+        assert!(result.is_err());
+        // The batch is rejected as a whole, including the profile selection.
+        assert_eq!(
+            params.get([GRAVITY, LANDING_MAX_TILT, LOAD_PROFILE]),
+            before
+        );
+        assert!(!changes.changed());
+    }
+
+    #[test]
+    fn test_failed_actions_are_all_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let params = parameters_with_profiles(dir.path(), &[("bad", r#"{"gravity": -5}"#)]);
+        let save = Parameter {
+            name: SAVE_PROFILE.to_string(),
+            r#type: None,
+            value: Some(ParameterValue::String("not a name!".into())),
+        };
+        let error = params
+            .set(vec![save, number(LOAD_PROFILE, 1.0)])
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("failed to save profile"), "{error}");
+        assert!(error.contains("failed to load profile"), "{error}");
+    }
+
+    #[test]
+    fn test_profile_load_updates_each_parameter_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let params =
+            parameters_with_profiles(dir.path(), &[("tilted", r#"{"landing_max_tilt": 12}"#)]);
+        let updated = params
+            .set(vec![number(DIFFICULTY, 0.0), number(LOAD_PROFILE, 1.0)])
+            .unwrap();
+        let tilts: Vec<_> = updated
+            .iter()
+            .filter(|p| p.name == LANDING_MAX_TILT)
+            .collect();
+        // The difficulty preset sets the tilt too, but the profile's value is the one reported.
+        assert_eq!(tilts.len(), 1);
+        assert_eq!(tilts[0].value, Some(ParameterValue::Number(12.0)));
+        assert_eq!(params.registry.read().get_f32(LANDING_MAX_TILT), Some(12.0));
+    }
+
+    #[test]
+    fn test_invalid_scenario_sets_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let scenarios_dir = dir.path().join("scenarios");
+        std::fs::create_dir(&scenarios_dir).unwrap();
+        // The parameters are fine, but the landing zone is off the map.
+        std::fs::write(
+            scenarios_dir.join("far.json"),
+            r#"{"parameters": {"body": "mars"}, "landing_zone": [5000, 0]}"#,
+        )
+        .unwrap();
+        let params = Parameters::load(
+            &dir.path().join("parameters.json"),
+            dir.path(),
+            &scenarios_dir,
+        )
+        .unwrap();
+        params.set(vec![number(GRAVITY, 2.0)]).unwrap();
+        let before = params.get(Vec::<String>::new());
+        let error = params.set(vec![number(SCENARIO, 1.0)]).unwrap_err();
+        assert!(format!("{error:#}").contains("landing zone"), "{error:#}");
+        assert_eq!(params.get(Vec::<String>::new()), before);
     }
 }
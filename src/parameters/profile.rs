@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Serialize;
+use tempfile::NamedTempFile;
+
+/// Returns the path of the named profile or scenario in `dir`.
+///
+/// Names may only contain ASCII letters, digits, `-` and `_`.
+pub fn path(dir: &Path, name: &str) -> anyhow::Result<PathBuf> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    anyhow::ensure!(valid, "invalid name");
+    Ok(dir.join(format!("{name}.json")))
+}
+
+/// Reads parameter values from a JSON file.
+pub fn read(path: &Path) -> anyhow::Result<BTreeMap<String, serde_json::Value>> {
+    let file = std::fs::File::open(path).with_context(|| format!("open {path:?}"))?;
+    serde_json::from_reader(BufReader::new(file)).with_context(|| format!("parse {path:?}"))
+}
+
+/// Writes parameter values, or their schema, to a JSON file, creating its directory if necessary.
+pub fn write(path: &Path, values: &impl Serialize) -> anyhow::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir).with_context(|| format!("create {dir:?}"))?;
+    let file = NamedTempFile::new_in(dir).context("create parameters file")?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, values).context("write parameters")?;
+    writer
+        .into_inner()
+        .context("flush parameters")?
+        .persist(path)
+        .with_context(|| format!("rename parameters file to {path:?}"))?;
+    Ok(())
+}
+
+/// Returns the names of the profiles or scenarios in `dir`, in alphabetical order.
+///
+/// Files with names that `path` would reject are skipped. A missing directory has none.
+pub fn list(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut names: Vec<_> = entries
+        .filter_map(|entry| {
+            let file = entry.ok()?.path();
+            if file.extension()? != "json" {
+                return None;
+            }
+            let name = file.file_stem()?.to_str()?.to_string();
+            path(dir, &name).is_ok().then_some(name)
+        })
+        .collect();
+    names.sort();
+    names
+}
@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use foxglove::{ChannelId, Context, FoxgloveError, Metadata, RawChannel, Sink, SinkId};
+use parking_lot::Mutex;
+use tempfile::NamedTempFile;
+
+use crate::events::Event;
+
+type Writer = mcap::Writer<BufWriter<NamedTempFile>>;
+
+/// An MCAP recording of a single game.
+///
+/// The recording is written to a temporary file, which is only persisted when the recording is
+/// finished.
+///
+/// Events are recorded as time-stamped messages on the `/events` channel as they happen, which is
+/// how they appear on the timeline. They're also collected into an `events` metadata record, which
+/// summarizes the game for search. MCAP metadata has no timestamps, so it doesn't appear on the
+/// timeline.
+pub struct Recording {
+    sink: Arc<RecordingSink>,
+    events: Vec<String>,
+}
+
+impl Recording {
+    /// Starts recording everything that's logged to a temporary file in `dir`.
+    pub fn start(dir: &Path) -> anyhow::Result<Self> {
+        let file = NamedTempFile::new_in(dir).context("create recording file")?;
+        let writer = mcap::Writer::new(BufWriter::new(file)).context("start recording")?;
+        let sink = Arc::new(RecordingSink {
+            id: SinkId::next(),
+            state: Mutex::new(Some(WriterState {
+                writer,
+                channels: HashMap::new(),
+            })),
+        });
+        Context::get_default().add_sink(sink.clone());
+        Ok(Self {
+            sink,
+            events: vec![],
+        })
+    }
+
+    /// Writes a named metadata record.
+    pub fn add_metadata(&mut self, name: &str, metadata: BTreeMap<String, String>) {
+        let result = self.sink.with_writer(|writer| {
+            writer.write_metadata(&mcap::records::Metadata {
+                name: name.into(),
+                metadata,
+            })
+        });
+        if let Err(e) = result {
+            eprintln!("failed to write {name} metadata: {e}");
+        }
+    }
+
+    /// Logs an event, and adds it to the recording's metadata.
+    pub fn log_event(&mut self, mission_time: f32, event: &Event) {
+        event.log(mission_time);
+        self.events.push(event.to_json(mission_time));
+    }
+
+    /// Finishes the recording, and persists it at `path`.
+    pub fn finish(mut self, path: &Path) -> anyhow::Result<()> {
+        Context::get_default().remove_sink(self.sink.id);
+        let events = std::mem::take(&mut self.events)
+            .into_iter()
+            .enumerate()
+            .map(|(i, event)| (format!("{i:04}"), event))
+            .collect();
+        self.add_metadata("events", events);
+        let Some(mut state) = self.sink.state.lock().take() else {
+            anyhow::bail!("recording already finished");
+        };
+        state.writer.finish().context("finish recording")?;
+        state
+            .writer
+            .into_inner()
+            .into_inner()
+            .context("flush recording")?
+            .persist(path)
+            .context("rename recording file")?;
+        Ok(())
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        // Stop recording if the game ends without finishing it. The temporary file is deleted.
+        Context::get_default().remove_sink(self.sink.id);
+    }
+}
+
+struct WriterState {
+    writer: Writer,
+    /// Maps channel IDs to the IDs of the corresponding channels in the recording.
+    channels: HashMap<ChannelId, u16>,
+}
+
+/// A sink that writes everything that's logged to an MCAP file.
+///
+/// This does the same as the SDK's MCAP writer, but keeps hold of the underlying writer, so that
+/// metadata can be written to the recording as it goes.
+struct RecordingSink {
+    id: SinkId,
+    state: Mutex<Option<WriterState>>,
+}
+
+impl RecordingSink {
+    fn with_writer<T>(
+        &self,
+        f: impl FnOnce(&mut Writer) -> Result<T, mcap::McapError>,
+    ) -> Result<T, FoxgloveError> {
+        let mut state = self.state.lock();
+        let state = state.as_mut().ok_or(FoxgloveError::SinkClosed)?;
+        Ok(f(&mut state.writer)?)
+    }
+}
+
+impl Sink for RecordingSink {
+    fn id(&self) -> SinkId {
+        self.id
+    }
+
+    fn log(
+        &self,
+        channel: &RawChannel,
+        msg: &[u8],
+        metadata: &Metadata,
+    ) -> Result<(), FoxgloveError> {
+        let mut state = self.state.lock();
+        let WriterState { writer, channels } = state.as_mut().ok_or(FoxgloveError::SinkClosed)?;
+        let channel_id = match channels.get(&channel.id()) {
+            Some(id) => *id,
+            None => {
+                let schema_id = match channel.schema() {
+                    Some(schema) => {
+                        writer.add_schema(&schema.name, &schema.encoding, &schema.data)?
+                    }
+                    // Zero means that the channel has no schema.
+                    None => 0,
+                };
+                let id = writer.add_channel(
+                    schema_id,
+                    channel.topic(),
+                    channel.message_encoding(),
+                    channel.metadata(),
+                )?;
+                channels.insert(channel.id(), id);
+                id
+            }
+        };
+        writer.write_to_known_channel(
+            &mcap::records::MessageHeader {
+                channel_id,
+                sequence: metadata.sequence,
+                log_time: metadata.log_time,
+                publish_time: metadata.publish_time,
+            },
+            msg,
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_has_events_and_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("game.mcap");
+        let mut recording = Recording::start(dir.path()).unwrap();
+        recording.add_metadata("scenario", BTreeMap::from([("name".into(), "test".into())]));
+        recording.log_event(0.0, &Event::GameStart { seed: 1 });
+        recording.log_event(1.5, &Event::Reset);
+        recording.finish(&path).unwrap();
+
+        let buf = std::fs::read(&path).unwrap();
+        // Other tests may be logging at the same time, so only count the events.
+        let events = mcap::MessageStream::new(&buf)
+            .unwrap()
+            .filter(|message| message.as_ref().unwrap().channel.topic == "/events")
+            .count();
+        assert_eq!(events, 2);
+
+        let summary = mcap::read::Summary::read(&buf).unwrap().unwrap();
+        let metadata: BTreeMap<_, _> = summary
+            .metadata_indexes
+            .iter()
+            .map(|index| {
+                let metadata = mcap::read::metadata(&buf, index).unwrap();
+                (metadata.name, metadata.metadata)
+            })
+            .collect();
+        assert_eq!(metadata["scenario"]["name"], "test");
+        assert_eq!(metadata["events"].len(), 2);
+        assert!(metadata["events"]["0001"].contains(r#""type":"reset""#));
+    }
+}
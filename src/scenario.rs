@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+use std::io::BufReader;
+use std::path::Path;
+
+use anyhow::Context;
+use glam::{Quat, Vec2, Vec3};
+use serde::Deserialize;
+
+use crate::lander::InitialState;
+
+/// A complete game setup, loaded from a JSON file.
+///
+/// The vehicle, body, faults, landing criteria and everything else that's a parameter are set
+/// through `parameters`, which are applied on top of the defaults when the scenario is selected.
+/// Anything else that the scenario leaves out is generated from the seed, as usual.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scenario {
+    /// Name of the scenario file, without the extension.
+    #[serde(skip)]
+    pub name: String,
+    /// What the scenario is about.
+    pub description: String,
+    /// Seed for the terrain, and everything else that's random. Defaults to the seed parameter.
+    pub seed: Option<u64>,
+    /// Parameter values, in the same format as the parameters file.
+    pub parameters: BTreeMap<String, serde_json::Value>,
+    /// Position of the landing zone relative to the center of the landscape, in meters.
+    /// Defaults to a random position.
+    pub landing_zone: Option<[f32; 2]>,
+    /// Initial state of the lander. Defaults to the state set by the parameters.
+    pub initial_state: ScenarioState,
+    /// Time allowed to land, in seconds. Unlimited by default.
+    pub time_limit: Option<f32>,
+}
+
+/// Overrides for the lander's initial state.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScenarioState {
+    /// Position relative to the landing zone, in meters.
+    pub position: Option<[f32; 3]>,
+    /// Velocity, in meters/s.
+    pub velocity: Option<[f32; 3]>,
+    /// Attitude, as an `[x, y, z, w]` quaternion, which is normalized.
+    pub rotation: Option<[f32; 4]>,
+    /// Angular velocity in body coordinates, in radians/s.
+    pub angular_velocity: Option<[f32; 3]>,
+    /// Target vertical velocity, in meters/s.
+    pub vertical_velocity_target: Option<f32>,
+    /// Descent fuel mass, in kg.
+    pub fuel_mass: Option<f32>,
+}
+
+impl Scenario {
+    /// Reads a scenario from a JSON file.
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path).with_context(|| format!("open {path:?}"))?;
+        let mut scenario: Self = serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("parse {path:?}"))?;
+        if let Some(rotation) = scenario.initial_state.rotation {
+            anyhow::ensure!(
+                Quat::from_array(rotation).length() > 0.0,
+                "the initial rotation must be a non-zero quaternion"
+            );
+        }
+        scenario.name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(scenario)
+    }
+
+    pub fn landing_zone(&self) -> Option<Vec2> {
+        self.landing_zone.map(Vec2::from_array)
+    }
+
+    /// Overrides the parts of the lander's initial state that the scenario sets.
+    pub fn apply(&self, state: &mut InitialState) {
+        let overrides = &self.initial_state;
+        if let Some(position) = overrides.position {
+            state.position = Vec3::from_array(position);
+        }
+        if let Some(velocity) = overrides.velocity {
+            state.velocity = Vec3::from_array(velocity);
+        }
+        if let Some(rotation) = overrides.rotation {
+            state.rotation = Quat::from_array(rotation).normalize();
+        }
+        if let Some(angular_velocity) = overrides.angular_velocity {
+            state.angular_velocity = Vec3::from_array(angular_velocity);
+        }
+        if let Some(target) = overrides.vertical_velocity_target {
+            state.vertical_velocity_target = target;
+        }
+        if let Some(fuel_mass) = overrides.fuel_mass {
+            state.fuel_mass = fuel_mass;
+        }
+    }
+
+    /// Returns the scenario's name and description, as MCAP metadata.
+    pub fn to_metadata(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("name".into(), self.name.clone()),
+            ("description".into(), self.description.clone()),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(json: &str) -> anyhow::Result<Scenario> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.json");
+        std::fs::write(&path, json).unwrap();
+        Scenario::read(&path)
+    }
+
+    fn initial_state() -> InitialState {
+        InitialState {
+            position: Vec3::Z * 100.0,
+            velocity: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            angular_velocity: Vec3::ZERO,
+            vertical_velocity_target: -6.0,
+            fuel_mass: 1000.0,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let scenario = read(
+            r#"{
+                "seed": 7,
+                "parameters": {"body": "mars"},
+                "landing_zone": [10, -20],
+                "initial_state": {"rotation": [0, 0, 1, 1], "fuel_mass": 500},
+                "time_limit": 60
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(scenario.name, "test");
+        assert_eq!(scenario.seed, Some(7));
+        assert_eq!(scenario.parameters["body"], "mars");
+        assert_eq!(scenario.landing_zone(), Some(Vec2::new(10.0, -20.0)));
+        assert_eq!(scenario.time_limit, Some(60.0));
+        assert_eq!(scenario.initial_state.velocity, None);
+
+        // Everything is optional.
+        let scenario = read("{}").unwrap();
+        assert_eq!(scenario.seed, None);
+        assert!(scenario.parameters.is_empty());
+    }
+
+    #[test]
+    fn test_reject_invalid() {
+        assert!(read(r#"{"seeds": 7}"#).is_err());
+        assert!(read(r#"{"initial_state": {"altitude": 100}}"#).is_err());
+        assert!(read(r#"{"initial_state": {"rotation": [0, 0, 0, 0]}}"#).is_err());
+        assert!(read(r#"{"initial_state": {"rotation": [0, 0, 1]}}"#).is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let scenario = read(
+            r#"{"initial_state": {
+                "velocity": [1, 2, -3],
+                "rotation": [0, 0, 1, 1],
+                "angular_velocity": [0.1, 0, 0],
+                "fuel_mass": 500
+            }}"#,
+        )
+        .unwrap();
+        let mut state = initial_state();
+        scenario.apply(&mut state);
+        assert_eq!(state.velocity, Vec3::new(1.0, 2.0, -3.0));
+        assert!(
+            state
+                .rotation
+                .abs_diff_eq(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2), 1e-6)
+        );
+        assert_eq!(state.angular_velocity, Vec3::new(0.1, 0.0, 0.0));
+        assert_eq!(state.fuel_mass, 500.0);
+        // What the scenario leaves out is left alone.
+        assert_eq!(state.position, Vec3::Z * 100.0);
+        assert_eq!(state.vertical_velocity_target, -6.0);
+    }
+}
@@ -0,0 +1,264 @@
+use foxglove::LazyChannel;
+use glam::{Quat, Vec3};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+use crate::body::Body;
+use crate::faults::Fault;
+use crate::lander::Lander;
+use crate::landscape::Landscape;
+use crate::parameters::Parameters;
+
+mod imu;
+mod nav;
+mod radar;
+use imu::{Imu, ImuReading};
+use nav::NavFilter;
+use radar::{Radar, RadarReading};
+
+static IMU: LazyChannel<ImuMsg> = LazyChannel::new("/imu");
+static NAV: LazyChannel<NavMsg> = LazyChannel::new("/nav");
+static RADAR: LazyChannel<RadarMsg> = LazyChannel::new("/radar");
+
+fn init_channels() {
+    IMU.init();
+    NAV.init();
+    RADAR.init();
+}
+
+type Vector = [f64; 3];
+
+fn vector(v: Vec3) -> Vector {
+    [v.x.into(), v.y.into(), v.z.into()]
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+struct RadarMsg {
+    enabled: bool,
+    true_altitude: f64,
+    true_velocity: Vector,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sensed_altitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sensed_velocity: Option<Vector>,
+    locked: bool,
+    dropout: bool,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+struct ImuMsg {
+    true_specific_force: Vector,
+    true_angular_velocity: Vector,
+    sensed_specific_force: Vector,
+    sensed_angular_velocity: Vector,
+}
+
+#[derive(Serialize, schemars::JsonSchema)]
+struct NavMsg {
+    enabled: bool,
+    true_position: Vector,
+    true_velocity: Vector,
+    true_attitude: Vector,
+    estimated_position: Vector,
+    estimated_velocity: Vector,
+    estimated_attitude: Vector,
+    position_error: f64,
+    velocity_error: f64,
+    attitude_error: f64,
+}
+
+/// The state of the lander, as far as the guidance system can tell.
+#[derive(Debug, Clone, Copy)]
+pub struct StateEstimate {
+    /// Altitude above the terrain directly below the lander.
+    pub altitude: f32,
+    /// Position relative to the landing zone frame.
+    pub position: Vec3,
+    /// Velocity relative to the landing zone frame.
+    pub velocity: Vec3,
+    /// Orientation relative to the landing zone frame.
+    pub rotation: Quat,
+    /// Angular velocity, in body coordinates.
+    pub angular_velocity: Vec3,
+}
+
+impl StateEstimate {
+    /// Returns the true state of the lander.
+    fn truth(lander: &Lander, landscape: &Landscape) -> Self {
+        let position = lander.position();
+        Self {
+            altitude: position.z - landscape.ground_height(position.truncate()),
+            position,
+            velocity: lander.velocity(),
+            rotation: lander.rotation(),
+            angular_velocity: lander.angular_velocity(),
+        }
+    }
+
+    /// Speed along the local vertical of `body`, positive upwards.
+    pub fn vertical_speed(&self, body: &Body) -> f32 {
+        self.velocity.dot(body.up(self.position))
+    }
+
+    /// Speed across the local vertical of `body`.
+    pub fn horizontal_speed(&self, body: &Body) -> f32 {
+        self.velocity
+            .reject_from_normalized(body.up(self.position))
+            .length()
+    }
+
+    /// Tilt from upright, relative to the local vertical of `body`, in radians.
+    pub fn tilt(&self, body: &Body) -> f32 {
+        let up = self.rotation * Vec3::Z;
+        up.angle_between(body.up(self.position))
+    }
+}
+
+/// Simulated sensors.
+pub struct Sensors {
+    radar_enabled: bool,
+    rng: ChaCha8Rng,
+    radar: Radar,
+    imu: Imu,
+    nav: Option<NavFilter>,
+    truth: StateEstimate,
+    true_specific_force: Vec3,
+    radar_reading: RadarReading,
+    /// Whether the radar has locked onto the surface at any point during the game.
+    radar_acquired: bool,
+    imu_reading: ImuReading,
+    estimate: StateEstimate,
+}
+
+impl Sensors {
+    /// Creates a new sensor suite.
+    ///
+    /// The sensor noise is seeded from `rng`, so that it is deterministic for a given game seed.
+    pub fn new<R: Rng>(
+        rng: &mut R,
+        params: &Parameters,
+        lander: &Lander,
+        landscape: &Landscape,
+    ) -> Self {
+        init_channels();
+        let mut rng = ChaCha8Rng::from_rng(rng);
+        let imu = Imu::new(&mut rng, params);
+        let nav = params
+            .nav_filter_enabled()
+            .then(|| NavFilter::new(lander, imu.accel_noise()));
+        let truth = StateEstimate::truth(lander, landscape);
+        Self {
+            radar_enabled: params.radar_enabled(),
+            rng,
+            radar: Radar::new(params),
+            imu,
+            nav,
+            truth,
+            true_specific_force: Vec3::ZERO,
+            radar_reading: RadarReading::default(),
+            radar_acquired: false,
+            imu_reading: ImuReading::default(),
+            estimate: truth,
+        }
+    }
+
+    /// Samples the sensors, and updates the state estimate.
+    pub fn update(&mut self, dt: f32, lander: &Lander, landscape: &Landscape) {
+        self.truth = StateEstimate::truth(lander, landscape);
+        self.true_specific_force = lander.specific_force();
+        self.radar_reading = self.radar.sample(&mut self.rng, dt, lander, landscape);
+        self.radar_acquired |= self.radar_reading.locked;
+        self.imu_reading = self.imu.sample(&mut self.rng, lander);
+
+        self.estimate = if let Some(nav) = &mut self.nav {
+            // The navigation filter fuses the IMU and the landing radar, or runs on the IMU alone
+            // if the radar is disabled.
+            nav.predict(dt, &self.imu_reading);
+            if self.radar_enabled {
+                nav.correct(
+                    &self.radar_reading,
+                    self.radar.range_noise(),
+                    self.radar.velocity_noise(),
+                    landscape,
+                );
+            }
+            nav.estimate(landscape)
+        } else if self.radar_enabled && self.radar_acquired {
+            // Fly on radar, holding onto the last good measurement while the radar isn't
+            // reporting. Until the radar first locks on, the estimate follows the inertial
+            // state, which is taken to be the truth.
+            StateEstimate {
+                altitude: self
+                    .radar_reading
+                    .altitude
+                    .unwrap_or(self.estimate.altitude),
+                velocity: self
+                    .radar_reading
+                    .velocity
+                    .unwrap_or(self.estimate.velocity),
+                ..self.truth
+            }
+        } else {
+            self.truth
+        };
+    }
+
+    /// Applies a fault to the sensors.
+    pub fn apply_fault(&mut self, fault: &Fault) {
+        if let Fault::RadarFreeze = fault {
+            self.radar.freeze(self.radar_reading);
+        }
+    }
+
+    /// Returns the current state estimate.
+    pub fn estimate(&self) -> &StateEstimate {
+        &self.estimate
+    }
+
+    /// Logs true and sensed values.
+    pub fn log(&self) {
+        RADAR.log(&RadarMsg {
+            enabled: self.radar_enabled,
+            true_altitude: self.truth.altitude.into(),
+            true_velocity: vector(self.truth.velocity),
+            sensed_altitude: self.radar_reading.altitude.map(f64::from),
+            sensed_velocity: self.radar_reading.velocity.map(vector),
+            locked: self.radar_reading.locked,
+            dropout: self.radar_reading.dropout,
+        });
+        IMU.log(&ImuMsg {
+            true_specific_force: vector(self.true_specific_force),
+            true_angular_velocity: vector(self.truth.angular_velocity),
+            sensed_specific_force: vector(self.imu_reading.specific_force),
+            sensed_angular_velocity: vector(self.imu_reading.angular_velocity),
+        });
+        let attitude = |q: Quat| {
+            let (x, y, z) = q.to_euler(glam::EulerRot::XYZ);
+            vector(Vec3::new(x, y, z))
+        };
+        NAV.log(&NavMsg {
+            enabled: self.nav.is_some(),
+            true_position: vector(self.truth.position),
+            true_velocity: vector(self.truth.velocity),
+            true_attitude: attitude(self.truth.rotation),
+            estimated_position: vector(self.estimate.position),
+            estimated_velocity: vector(self.estimate.velocity),
+            estimated_attitude: attitude(self.estimate.rotation),
+            position_error: self.estimate.position.distance(self.truth.position).into(),
+            velocity_error: self.estimate.velocity.distance(self.truth.velocity).into(),
+            attitude_error: self
+                .estimate
+                .rotation
+                .angle_between(self.truth.rotation)
+                .into(),
+        });
+    }
+}
+
+/// Returns a sample from the standard normal distribution, using the Box-Muller transform.
+fn standard_normal<R: Rng>(rng: &mut R) -> f32 {
+    let u1: f32 = rng.random_range(f32::EPSILON..1.0);
+    let u2: f32 = rng.random();
+    (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos()
+}
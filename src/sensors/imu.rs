@@ -0,0 +1,64 @@
+use glam::Vec3;
+use rand::prelude::*;
+
+use super::standard_normal;
+use crate::lander::Lander;
+use crate::parameters::Parameters;
+
+/// A single IMU measurement, in body coordinates.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ImuReading {
+    /// Measured non-gravitational acceleration.
+    pub specific_force: Vec3,
+    /// Measured angular velocity.
+    pub angular_velocity: Vec3,
+}
+
+/// An inertial measurement unit.
+///
+/// Each axis of the accelerometer and gyro has a fixed turn-on bias, drawn when the IMU is
+/// created, and white noise on every sample.
+pub struct Imu {
+    accel_bias: Vec3,
+    accel_noise: f32,
+    gyro_bias: Vec3,
+    gyro_noise: f32,
+}
+
+impl Imu {
+    pub fn new<R: Rng>(rng: &mut R, params: &Parameters) -> Self {
+        let mut bias = |sigma: f32| {
+            Vec3::new(
+                standard_normal(rng),
+                standard_normal(rng),
+                standard_normal(rng),
+            ) * sigma
+        };
+        Self {
+            accel_bias: bias(params.imu_accel_bias()),
+            accel_noise: params.imu_accel_noise(),
+            gyro_bias: bias(params.imu_gyro_bias()),
+            gyro_noise: params.imu_gyro_noise(),
+        }
+    }
+
+    /// Returns the accelerometer noise standard deviation.
+    pub fn accel_noise(&self) -> f32 {
+        self.accel_noise
+    }
+
+    /// Samples the IMU.
+    pub fn sample<R: Rng>(&self, rng: &mut R, lander: &Lander) -> ImuReading {
+        let mut noise = |sigma: f32| {
+            Vec3::new(
+                standard_normal(rng),
+                standard_normal(rng),
+                standard_normal(rng),
+            ) * sigma
+        };
+        ImuReading {
+            specific_force: lander.specific_force() + self.accel_bias + noise(self.accel_noise),
+            angular_velocity: lander.angular_velocity() + self.gyro_bias + noise(self.gyro_noise),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use glam::{EulerRot, Mat2, Quat, Vec2, Vec3};
+
+use super::StateEstimate;
+use super::imu::ImuReading;
+use super::radar::RadarReading;
+use crate::lander::{Lander, LanderConfig};
+use crate::landscape::Landscape;
+
+/// Initial position uncertainty (standard deviation), in meters.
+const INITIAL_POSITION_SIGMA: f32 = 1.0;
+
+/// Initial velocity uncertainty (standard deviation), in meters/s.
+const INITIAL_VELOCITY_SIGMA: f32 = 0.1;
+
+/// Floor for measurement noise standard deviations, so that the filter never fully trusts a
+/// noiseless sensor.
+const MIN_MEASUREMENT_SIGMA: f32 = 0.01;
+
+/// A navigation filter.
+///
+/// Attitude is propagated by integrating the gyro. Position and velocity are propagated by
+/// integrating the accelerometer, and corrected by landing radar measurements. Since the
+/// propagation is linear in position and velocity once attitude is known, each axis is handled by
+/// an independent two-state Kalman filter.
+pub struct NavFilter {
+    position: Vec3,
+    velocity: Vec3,
+    rotation: Quat,
+    angular_velocity: Vec3,
+    covariance: [Mat2; 3],
+    accel_noise: f32,
+    config: LanderConfig,
+}
+
+impl NavFilter {
+    /// Creates a new navigation filter, initialized from the lander's true state.
+    pub fn new(lander: &Lander, accel_noise: f32) -> Self {
+        let covariance = Mat2::from_diagonal(Vec2::new(
+            INITIAL_POSITION_SIGMA.powi(2),
+            INITIAL_VELOCITY_SIGMA.powi(2),
+        ));
+        Self {
+            position: lander.position(),
+            velocity: lander.velocity(),
+            rotation: lander.rotation(),
+            angular_velocity: lander.angular_velocity(),
+            covariance: [covariance; 3],
+            accel_noise: accel_noise.max(MIN_MEASUREMENT_SIGMA),
+            config: lander.config().clone(),
+        }
+    }
+
+    /// Propagates the state estimate with an IMU reading.
+    pub fn predict(&mut self, dt: f32, imu: &ImuReading) {
+        self.angular_velocity = imu.angular_velocity;
+        self.rotation *= Quat::from_euler(
+            EulerRot::XYZ,
+            imu.angular_velocity.x * dt,
+            imu.angular_velocity.y * dt,
+            imu.angular_velocity.z * dt,
+        );
+        let accel = self.rotation * imu.specific_force + self.config.gravity_at(self.position);
+        self.position += self.velocity * dt + 0.5 * accel * dt * dt;
+        self.velocity += accel * dt;
+
+        // Constant-acceleration process model, driven by accelerometer noise.
+        let f = Mat2::from_cols(Vec2::new(1.0, 0.0), Vec2::new(dt, 1.0));
+        let q = self.accel_noise.powi(2)
+            * Mat2::from_cols(
+                Vec2::new(dt.powi(4) / 4.0, dt.powi(3) / 2.0),
+                Vec2::new(dt.powi(3) / 2.0, dt.powi(2)),
+            );
+        for p in &mut self.covariance {
+            *p = f * *p * f.transpose() + q;
+        }
+    }
+
+    /// Corrects the state estimate with a landing radar reading.
+    pub fn correct(
+        &mut self,
+        radar: &RadarReading,
+        range_noise: f32,
+        velocity_noise: f32,
+        landscape: &Landscape,
+    ) {
+        if let Some(altitude) = radar.altitude {
+            let ground = landscape.ground_height(self.position.truncate());
+            let r = range_noise.max(MIN_MEASUREMENT_SIGMA).powi(2);
+            self.update(2, Vec2::X, altitude + ground, r);
+        }
+        if let Some(velocity) = radar.velocity {
+            let r = velocity_noise.max(MIN_MEASUREMENT_SIGMA).powi(2);
+            for axis in 0..3 {
+                self.update(axis, Vec2::Y, velocity[axis], r);
+            }
+        }
+    }
+
+    /// Applies a scalar measurement `z` with observation vector `h` and variance `r` to the
+    /// position and velocity states of the given axis.
+    fn update(&mut self, axis: usize, h: Vec2, z: f32, r: f32) {
+        let mut x = Vec2::new(self.position[axis], self.velocity[axis]);
+        let p = self.covariance[axis];
+        let ph = p * h;
+        let k = ph / (h.dot(ph) + r);
+        x += k * (z - h.dot(x));
+        self.covariance[axis] = (Mat2::IDENTITY - outer(k, h)) * p;
+        self.position[axis] = x.x;
+        self.velocity[axis] = x.y;
+    }
+
+    /// Returns the current state estimate.
+    pub fn estimate(&self, landscape: &Landscape) -> StateEstimate {
+        StateEstimate {
+            altitude: self.position.z - landscape.ground_height(self.position.truncate()),
+            position: self.position,
+            velocity: self.velocity,
+            rotation: self.rotation,
+            angular_velocity: self.angular_velocity,
+        }
+    }
+}
+
+/// Returns the outer product of two vectors.
+fn outer(a: Vec2, b: Vec2) -> Mat2 {
+    Mat2::from_cols(a * b.x, a * b.y)
+}
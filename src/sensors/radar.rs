@@ -0,0 +1,122 @@
+use glam::Vec3;
+use rand::prelude::*;
+
+use super::standard_normal;
+use crate::lander::Lander;
+use crate::landscape::Landscape;
+use crate::parameters::Parameters;
+
+/// Duration of a radar dropout, in seconds.
+const DROPOUT_DURATION_S: f32 = 1.0;
+
+/// A single radar measurement.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RadarReading {
+    /// Altitude, derived from the slant range along the radar boresight.
+    pub altitude: Option<f32>,
+    /// Velocity, derived from the velocity beams.
+    pub velocity: Option<Vec3>,
+    /// Whether the radar has locked onto the surface.
+    pub locked: bool,
+    /// Whether the radar is currently experiencing a dropout.
+    pub dropout: bool,
+}
+
+/// A landing radar, loosely modeled on the Apollo LM's.
+///
+/// The radar measures slant range along the lander's -z axis, and velocity along a set of beams
+/// fixed to the lander body. It only reports once the lander has descended below its lock-on
+/// altitude, and it occasionally drops out altogether.
+pub struct Radar {
+    range_noise: f32,
+    range_bias: f32,
+    velocity_noise: f32,
+    lock_on_altitude: f32,
+    dropout_rate: f32,
+    dropout_remaining: f32,
+    frozen: Option<RadarReading>,
+}
+
+impl Radar {
+    pub fn new(params: &Parameters) -> Self {
+        Self {
+            range_noise: params.radar_range_noise(),
+            range_bias: params.radar_range_bias(),
+            velocity_noise: params.radar_velocity_noise(),
+            lock_on_altitude: params.radar_lock_on_altitude(),
+            dropout_rate: params.radar_dropout_rate(),
+            dropout_remaining: 0.0,
+            frozen: None,
+        }
+    }
+
+    /// Returns the range noise standard deviation.
+    pub fn range_noise(&self) -> f32 {
+        self.range_noise
+    }
+
+    /// Returns the velocity noise standard deviation.
+    pub fn velocity_noise(&self) -> f32 {
+        self.velocity_noise
+    }
+
+    /// Freezes the radar, so that it repeats the given reading indefinitely.
+    pub fn freeze(&mut self, reading: RadarReading) {
+        self.frozen = Some(reading);
+    }
+
+    /// Samples the radar.
+    pub fn sample<R: Rng>(
+        &mut self,
+        rng: &mut R,
+        dt: f32,
+        lander: &Lander,
+        landscape: &Landscape,
+    ) -> RadarReading {
+        if let Some(frozen) = self.frozen {
+            return frozen;
+        }
+
+        // Dropouts arrive as a Poisson process.
+        self.dropout_remaining = (self.dropout_remaining - dt).max(0.0);
+        if self.dropout_remaining <= 0.0 && rng.random::<f32>() < self.dropout_rate * dt {
+            self.dropout_remaining = DROPOUT_DURATION_S;
+        }
+        let dropout = self.dropout_remaining > 0.0;
+
+        // Measure slant range along the boresight, and convert it to altitude.
+        let boresight = lander.rotation() * -Vec3::Z;
+        let Some(hit) = landscape.raycast(lander.position(), boresight) else {
+            return RadarReading {
+                dropout,
+                ..Default::default()
+            };
+        };
+        let slant_range = hit.distance(lander.position());
+        let altitude = slant_range * boresight.dot(-Vec3::Z);
+        let locked = altitude <= self.lock_on_altitude;
+        if !locked || dropout {
+            return RadarReading {
+                locked,
+                dropout,
+                ..Default::default()
+            };
+        }
+
+        let sensed_range = slant_range + self.range_bias + self.range_noise * standard_normal(rng);
+        let noise = Vec3::new(
+            standard_normal(rng),
+            standard_normal(rng),
+            standard_normal(rng),
+        );
+        // The velocity beams are fixed to the body, so the noise is in body coordinates.
+        let body_velocity = lander.rotation().inverse() * lander.velocity();
+        let sensed_velocity = lander.rotation() * (body_velocity + noise * self.velocity_noise);
+        RadarReading {
+            altitude: Some(sensed_range * boresight.dot(-Vec3::Z)),
+            velocity: Some(sensed_velocity),
+            locked,
+            dropout,
+        }
+    }
+}